@@ -0,0 +1,82 @@
+use std::path::Path;
+
+use ahash::HashMap;
+use kira::{AudioManager, Decibels, Panning, sound::static_sound::StaticSoundData};
+use tracing::warn;
+
+use crate::camera::Camera;
+
+/// Handle to a sound loaded by [`Audio::load_dir`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoundId(usize);
+
+pub struct Audio {
+    manager: AudioManager,
+    sounds: Vec<StaticSoundData>,
+    names: HashMap<String, SoundId>,
+}
+
+impl Audio {
+    /// Distance in blocks at which spatial sounds fade out completely.
+    pub const MAX_DISTANCE: f32 = 24.0;
+
+    pub fn new(manager: AudioManager) -> Self {
+        Self {
+            manager,
+            sounds: Vec::new(),
+            names: HashMap::default(),
+        }
+    }
+
+    /// Loads every sound file in `path`, naming each after its file stem.
+    /// Files that fail to decode are skipped with a warning.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, path: P) {
+        let Ok(entries) = path.as_ref().read_dir() else {
+            return;
+        };
+
+        for path in entries.flatten().map(|entry| entry.path()).filter(|path| path.is_file()) {
+            let Some(name) = path.file_stem().and_then(|name| name.to_str()).map(ToOwned::to_owned) else {
+                continue;
+            };
+
+            match StaticSoundData::from_file(&path) {
+                Ok(sound) => {
+                    self.names.insert(name, SoundId(self.sounds.len()));
+                    self.sounds.push(sound);
+                }
+                Err(error) => warn!(target: "client/audio", "Failed to load {}: {error}", path.display()),
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<SoundId> {
+        self.names.get(name).copied()
+    }
+
+    pub fn play_sound(&mut self, id: SoundId) {
+        self.play(self.sounds[id.0].clone());
+    }
+
+    /// Plays a sound emitted at `position`, quieter with distance from the
+    /// listener and panned towards the side it comes from.
+    pub fn play_spatial(&mut self, id: SoundId, position: glam::Vec3, listener: &Camera) {
+        let offset = position - listener.position;
+        let gain = 1.0 - offset.length() / Self::MAX_DISTANCE;
+
+        if gain <= 0.0 {
+            return;
+        }
+
+        let panning = offset.normalize_or_zero().dot(listener.right).clamp(-1.0, 1.0);
+        let sound = self.sounds[id.0].clone().volume(Decibels(20.0 * gain.log10())).panning(Panning(panning));
+
+        self.play(sound);
+    }
+
+    fn play(&mut self, sound: StaticSoundData) {
+        if let Err(error) = self.manager.play(sound) {
+            warn!(target: "client/audio", "Failed to play sound: {error}");
+        }
+    }
+}
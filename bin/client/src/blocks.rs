@@ -1,5 +1,5 @@
 use mavelin_shared::Color;
-use mavelin_storage::ColorConfig;
+use mavelin_storage::{ColorConfig, SoundGroup};
 use mavelin_world::Biome;
 
 use crate::Block;
@@ -18,6 +18,18 @@ impl Block for AirBlock {
     fn droppable(&self) -> bool {
         false
     }
+
+    fn is_solid(&self) -> bool {
+        false
+    }
+
+    fn hardness(&self) -> f32 {
+        0.0
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::None
+    }
 }
 
 pub struct StoneBlock;
@@ -26,6 +38,10 @@ impl Block for StoneBlock {
     fn id(&self) -> &'static str {
         "stone"
     }
+
+    fn hardness(&self) -> f32 {
+        1.5
+    }
 }
 
 pub struct WaterBlock;
@@ -72,6 +88,14 @@ impl Block for WaterBlock {
     fn droppable(&self) -> bool {
         false
     }
+
+    fn hardness(&self) -> f32 {
+        100.0
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Liquid
+    }
 }
 
 pub struct DirtBlock;
@@ -80,6 +104,14 @@ impl Block for DirtBlock {
     fn id(&self) -> &'static str {
         "dirt"
     }
+
+    fn hardness(&self) -> f32 {
+        0.5
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Dirt
+    }
 }
 
 pub struct GrassBlock;
@@ -98,6 +130,14 @@ impl Block for GrassBlock {
                 .unwrap_or(color_config.base_foliage_color),
         )
     }
+
+    fn hardness(&self) -> f32 {
+        0.6
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Grass
+    }
 }
 
 pub struct SandBlock;
@@ -106,6 +146,14 @@ impl Block for SandBlock {
     fn id(&self) -> &'static str {
         "sand"
     }
+
+    fn hardness(&self) -> f32 {
+        0.5
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Sand
+    }
 }
 
 pub struct WoodBlock;
@@ -114,6 +162,14 @@ impl Block for WoodBlock {
     fn id(&self) -> &'static str {
         "wood"
     }
+
+    fn hardness(&self) -> f32 {
+        2.0
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Wood
+    }
 }
 
 pub struct OakLogBlock;
@@ -122,6 +178,14 @@ impl Block for OakLogBlock {
     fn id(&self) -> &'static str {
         "oak_log"
     }
+
+    fn hardness(&self) -> f32 {
+        2.0
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Wood
+    }
 }
 
 pub struct OakLeavesBlock;
@@ -148,6 +212,14 @@ impl Block for OakLeavesBlock {
     fn blocks_light(&self) -> bool {
         false
     }
+
+    fn hardness(&self) -> f32 {
+        0.2
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Grass
+    }
 }
 
 pub struct IceBlock;
@@ -168,6 +240,14 @@ impl Block for IceBlock {
     fn cull_if_same(&self) -> bool {
         true
     }
+
+    fn hardness(&self) -> f32 {
+        0.5
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Glass
+    }
 }
 
 pub struct GreenGlassBlock;
@@ -188,6 +268,14 @@ impl Block for GreenGlassBlock {
     fn cull_if_same(&self) -> bool {
         true
     }
+
+    fn hardness(&self) -> f32 {
+        0.3
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Glass
+    }
 }
 
 pub struct TorchBlock;
@@ -208,6 +296,14 @@ impl Block for TorchBlock {
     fn collidable(&self) -> bool {
         false
     }
+
+    fn hardness(&self) -> f32 {
+        0.0
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Wood
+    }
 }
 
 pub struct SnowBlock;
@@ -216,6 +312,14 @@ impl Block for SnowBlock {
     fn id(&self) -> &'static str {
         "snow"
     }
+
+    fn hardness(&self) -> f32 {
+        0.2
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Snow
+    }
 }
 
 #[allow(dead_code)]
@@ -246,6 +350,14 @@ impl Block for RoseBlock {
     fn cull_if_same(&self) -> bool {
         false
     }
+
+    fn hardness(&self) -> f32 {
+        0.0
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Grass
+    }
 }
 
 #[allow(dead_code)]
@@ -267,6 +379,14 @@ impl Block for BlueRoseBlock {
     fn cull_if_same(&self) -> bool {
         false
     }
+
+    fn hardness(&self) -> f32 {
+        0.0
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Grass
+    }
 }
 
 #[allow(dead_code)]
@@ -276,6 +396,10 @@ impl Block for CobbleStoneBlock {
     fn id(&self) -> &'static str {
         "cobblestone"
     }
+
+    fn hardness(&self) -> f32 {
+        2.0
+    }
 }
 
 #[allow(dead_code)]
@@ -285,6 +409,10 @@ impl Block for BricksBlock {
     fn id(&self) -> &'static str {
         "bricks"
     }
+
+    fn hardness(&self) -> f32 {
+        2.0
+    }
 }
 
 #[allow(dead_code)]
@@ -294,6 +422,10 @@ impl Block for StoneBricksBlock {
     fn id(&self) -> &'static str {
         "stone_bricks"
     }
+
+    fn hardness(&self) -> f32 {
+        1.5
+    }
 }
 
 #[allow(dead_code)]
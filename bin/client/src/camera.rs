@@ -1,5 +1,20 @@
 use mavelin_physics::{AabbSource, PhysicsContext, RayCastResult};
-use mavelin_shared::FrustumCulling;
+use mavelin_shared::{FrustumCulling, Lerp};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Perspective projection with the given vertical field of view (in
+    /// radians).
+    Perspective { fov: f32 },
+    /// Orthographic projection showing `height` world units vertically.
+    Orthographic { height: f32 },
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self::Perspective { fov: Camera::DEFAULT_FOV }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Camera {
@@ -13,8 +28,17 @@ pub struct Camera {
     pub front: glam::Vec3,
 
     pub looking_at: Option<RayCastResult>,
+    /// How far away [`Self::looking_at`] may be, in blocks.
+    pub reach: f32,
 
-    pub fov: f32,
+    /// Offset from the followed target along the camera's right, up and
+    /// backward axes. [`None`] puts the camera at the target (first person).
+    pub third_person_offset: Option<glam::Vec3>,
+    /// Point the camera follows, blocks are picked from here rather than from
+    /// the camera position.
+    pivot: glam::Vec3,
+
+    pub projection: Projection,
     pub aspect_ratio: f32,
     pub z_near: f32,
     pub z_far: f32,
@@ -23,6 +47,19 @@ pub struct Camera {
 }
 
 impl Camera {
+    pub const DEFAULT_FOV: f32 = 55f32.to_radians();
+    pub const DEFAULT_ORTHOGRAPHIC_HEIGHT: f32 = 64.0;
+    pub const DEFAULT_REACH: f32 = 20.0;
+    pub const DEFAULT_THIRD_PERSON_OFFSET: glam::Vec3 = glam::Vec3::new(0.0, 0.5, 4.0);
+    /// How quickly the camera catches up with its third person position, higher
+    /// is snappier.
+    const FOLLOW_SPEED: f32 = 12.0;
+    /// Furthest the camera may lag behind its third person position, keeps
+    /// fast turns from swinging the view around.
+    const MAX_FOLLOW_LAG: f32 = 1.5;
+    /// Distance kept between the camera and a wall it was pulled in by.
+    const WALL_MARGIN: f32 = 0.2;
+
     pub fn default() -> Self {
         let yaw = 0f32;
         let pitch = 0f32;
@@ -40,7 +77,10 @@ impl Camera {
             up,
             front,
             looking_at: None,
-            fov: 55f32.to_radians(),
+            reach: Self::DEFAULT_REACH,
+            third_person_offset: None,
+            pivot: glam::Vec3::ZERO,
+            projection: Projection::default(),
             z_near: 0.01,
             z_far: 10000.0,
             aspect_ratio: 1024.0 / 768.0,
@@ -50,7 +90,11 @@ impl Camera {
 
     #[inline]
     pub fn new(position: glam::Vec3) -> Self {
-        Self { position, ..Self::default() }
+        Self {
+            position,
+            pivot: position,
+            ..Self::default()
+        }
     }
 
     #[inline]
@@ -61,18 +105,62 @@ impl Camera {
     #[inline]
     pub fn set_position<T: AabbSource>(&mut self, context: &PhysicsContext<T>, position: glam::Vec3) {
         self.position = position;
+        self.pivot = position;
         self.update_looking_at(context);
         self.update_frustum();
     }
 
+    /// Moves the camera along with `target`. In third person the camera eases
+    /// towards its offset position over `delta` seconds, and is pulled in
+    /// front of any block between it and the target.
+    pub fn follow<T: AabbSource>(&mut self, context: &PhysicsContext<T>, target: glam::Vec3, delta: f32) {
+        let Some(offset) = self.third_person_offset else {
+            return self.set_position(context, target);
+        };
+
+        let desired = target + self.right * offset.x + self.up * offset.y - self.front * offset.z;
+        let blocked = context
+            .raycast(target.as_dvec3(), desired.as_dvec3(), false)
+            .filter(RayCastResult::is_block)
+            .map(|result| {
+                let hit = result.hit_vec.as_vec3();
+
+                hit + (target - hit).normalize_or_zero() * Self::WALL_MARGIN
+            });
+
+        self.position = blocked.unwrap_or_else(|| {
+            let position = Lerp::lerp_clamped(&self.position, &desired, 1.0 - (-Self::FOLLOW_SPEED * delta).exp());
+
+            desired + (position - desired).clamp_length_max(Self::MAX_FOLLOW_LAG)
+        });
+
+        self.pivot = target;
+        self.update_looking_at(context);
+        self.update_frustum();
+    }
+
+    /// Switches between first person and third person with
+    /// [`Self::DEFAULT_THIRD_PERSON_OFFSET`].
+    pub fn toggle_third_person(&mut self) {
+        self.third_person_offset = match self.third_person_offset {
+            Some(_) => None,
+            None => Some(Self::DEFAULT_THIRD_PERSON_OFFSET),
+        };
+    }
+
     #[inline]
     pub fn update_looking_at<T: AabbSource>(&mut self, context: &PhysicsContext<T>) {
-        const BLOCK_REACH_DISTANCE: f32 = 20f32;
+        self.looking_at = self.raycast(context, self.reach);
+    }
 
-        let origin = self.position.as_dvec3();
-        let target = origin + (self.front * BLOCK_REACH_DISTANCE).as_dvec3();
+    /// Returns the first block hit along the view direction within `reach`
+    /// blocks. Rays start at the followed target, so third person picks the
+    /// same blocks as first person.
+    pub fn raycast<T: AabbSource>(&self, context: &PhysicsContext<T>, reach: f32) -> Option<RayCastResult> {
+        let origin = self.pivot.as_dvec3();
+        let target = origin + (self.front * reach).as_dvec3();
 
-        self.looking_at = context.raycast(origin, target, true).filter(RayCastResult::is_block);
+        context.raycast(origin, target, true).filter(RayCastResult::is_block)
     }
 
     #[inline]
@@ -84,9 +172,39 @@ impl Camera {
         self.update_looking_at(context);
     }
 
+    /// Sets the field of view if the camera uses a perspective projection.
+    #[inline]
+    pub const fn set_fov(&mut self, value: f32) {
+        if let Projection::Perspective { fov } = &mut self.projection {
+            *fov = value;
+        }
+    }
+
+    /// Switches between perspective and orthographic projection, keeping
+    /// [`Self::DEFAULT_FOV`] and [`Self::DEFAULT_ORTHOGRAPHIC_HEIGHT`] as the
+    /// respective defaults.
+    pub fn toggle_projection(&mut self) {
+        self.projection = match self.projection {
+            Projection::Perspective { .. } => Projection::Orthographic {
+                height: Self::DEFAULT_ORTHOGRAPHIC_HEIGHT,
+            },
+            Projection::Orthographic { .. } => Projection::default(),
+        };
+
+        self.update_frustum();
+    }
+
     #[inline]
     pub fn projection(&self) -> glam::Mat4 {
-        glam::camera::rh::proj::directx::perspective(self.fov, self.aspect_ratio, self.z_near, self.z_far)
+        match self.projection {
+            Projection::Perspective { fov } => glam::camera::rh::proj::directx::perspective(fov, self.aspect_ratio, self.z_near, self.z_far),
+            Projection::Orthographic { height } => {
+                let half_height = height / 2.0;
+                let half_width = half_height * self.aspect_ratio;
+
+                glam::camera::rh::proj::directx::orthographic(-half_width, half_width, -half_height, half_height, self.z_near, self.z_far)
+            }
+        }
     }
 
     #[inline]
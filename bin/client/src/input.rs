@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use ahash::{HashMap, HashSet};
 use mavelin_engine::{KeyCode, KeyboardModifiers, MouseButton};
 
@@ -5,8 +7,12 @@ use mavelin_engine::{KeyCode, KeyboardModifiers, MouseButton};
 pub struct KeyboardController {
     pressed: HashSet<KeyCode>,
     pressed_once: HashSet<KeyCode>,
+    repeated: HashSet<KeyCode>,
     released: HashSet<KeyCode>,
     pub modifiers: KeyboardModifiers,
+    /// Drops the repeated presses the OS sends while a key is held, so that
+    /// [`Self::is_key_repeated`] never reports anything.
+    pub ignore_repeats: bool,
 }
 
 impl KeyboardController {
@@ -20,6 +26,15 @@ impl KeyboardController {
         self.pressed_once.contains(&key)
     }
 
+    /// Whether the OS sent a repeated press for `key` this frame because it is
+    /// being held. Useful for actions like deleting characters, while
+    /// [`Self::is_key_pressed_once`] fires once per physical press.
+    #[allow(dead_code)]
+    #[inline]
+    pub fn is_key_repeated(&self, key: KeyCode) -> bool {
+        self.repeated.contains(&key)
+    }
+
     #[inline]
     pub fn is_key_released(&self, key: KeyCode) -> bool {
         self.released.contains(&key)
@@ -28,18 +43,19 @@ impl KeyboardController {
     #[inline]
     pub fn clear(&mut self) {
         self.pressed_once.clear();
+        self.repeated.clear();
         self.released.clear();
     }
 
     #[inline]
     pub fn handle_keyboard_input(&mut self, code: KeyCode, is_pressed: bool, repeat: bool) {
         if is_pressed {
-            if !repeat {
-                self.pressed_once.insert(code);
-
-                if self.pressed.contains(&code) {
-                    self.pressed.remove(&code);
+            if repeat {
+                if !self.ignore_repeats {
+                    self.repeated.insert(code);
                 }
+            } else {
+                self.pressed_once.insert(code);
             }
 
             self.pressed.insert(code);
@@ -50,15 +66,44 @@ impl KeyboardController {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct MouseController {
     pub position: glam::Vec2,
+    /// Longest time between two presses of a button for the second one to
+    /// count as a double click.
+    pub double_click_interval: Duration,
     pressed_once: HashSet<MouseButton>,
     pressed: HashSet<MouseButton>,
     released: HashSet<MouseButton>,
+    double_clicked: HashSet<MouseButton>,
+    last_pressed_at: HashMap<MouseButton, Instant>,
+    /// Cursor position at the moment the first of the held buttons was
+    /// pressed.
+    drag_origin: Option<glam::Vec2>,
+    /// Cursor movement since the last [`MouseController::clear`] while a
+    /// button was held.
+    drag_delta: glam::Vec2,
+}
+
+impl Default for MouseController {
+    fn default() -> Self {
+        Self {
+            position: glam::Vec2::ZERO,
+            double_click_interval: Self::DEFAULT_DOUBLE_CLICK_INTERVAL,
+            pressed_once: HashSet::default(),
+            pressed: HashSet::default(),
+            released: HashSet::default(),
+            double_clicked: HashSet::default(),
+            last_pressed_at: HashMap::default(),
+            drag_origin: None,
+            drag_delta: glam::Vec2::ZERO,
+        }
+    }
 }
 
 impl MouseController {
+    pub const DEFAULT_DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
     #[allow(dead_code)]
     #[inline]
     pub fn is_pressed(&self, button: MouseButton) -> bool {
@@ -76,25 +121,79 @@ impl MouseController {
         self.released.contains(&button)
     }
 
+    /// Whether `button` was pressed this frame within
+    /// [`Self::double_click_interval`] of its previous press.
+    #[allow(dead_code)]
+    #[inline]
+    pub fn is_double_click(&self, button: MouseButton) -> bool {
+        self.double_clicked.contains(&button)
+    }
+
+    /// Cursor position where the current drag started, [`None`] while no
+    /// button is held.
+    #[allow(dead_code)]
+    #[inline]
+    pub const fn drag_origin(&self) -> Option<glam::Vec2> {
+        self.drag_origin
+    }
+
+    /// Cursor movement this frame while a button is held, [`None`] if no
+    /// button is held.
+    #[allow(dead_code)]
+    #[inline]
+    pub const fn drag_delta(&self) -> Option<glam::Vec2> {
+        if self.drag_origin.is_some() { Some(self.drag_delta) } else { None }
+    }
+
     #[inline]
     pub fn clear(&mut self) {
         self.pressed_once.clear();
         self.released.clear();
+        self.double_clicked.clear();
+        self.drag_delta = glam::Vec2::ZERO;
     }
 
     #[inline]
-    pub const fn handle_mouse_motion(&mut self, position: glam::Vec2) {
+    pub fn handle_mouse_motion(&mut self, position: glam::Vec2) {
+        if self.drag_origin.is_some() {
+            self.drag_delta += position - self.position;
+        }
+
         self.position = position;
     }
 
     #[inline]
     pub fn handle_mouse_button(&mut self, button: MouseButton, is_pressed: bool) {
+        self.handle_mouse_button_at(button, is_pressed, Instant::now());
+    }
+
+    fn handle_mouse_button_at(&mut self, button: MouseButton, is_pressed: bool, now: Instant) {
         if is_pressed {
+            if self.pressed.is_empty() {
+                self.drag_origin = Some(self.position);
+            }
+
+            // A third quick press starts a new double click instead of
+            // completing another one.
+            if self
+                .last_pressed_at
+                .remove(&button)
+                .is_some_and(|last| now.duration_since(last) <= self.double_click_interval)
+            {
+                self.double_clicked.insert(button);
+            } else {
+                self.last_pressed_at.insert(button, now);
+            }
+
             self.pressed_once.insert(button);
             self.pressed.insert(button);
         } else {
             self.pressed.remove(&button);
             self.released.insert(button);
+
+            if self.pressed.is_empty() {
+                self.drag_origin = None;
+            }
         }
     }
 }
@@ -139,3 +238,161 @@ impl Input {
         self.binds.get(name.as_ref()).is_some_and(|&key| self.keyboard.is_key_released(key))
     }
 }
+
+/// Keys that can be bound from the settings file.
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::Enter,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+];
+
+/// Looks up a key by its `KeyCode` variant name, e.g. `"KeyW"` or
+/// `"ShiftLeft"`.
+pub fn key_code_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.iter().copied().find(|key| format!("{key:?}") == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use mavelin_engine::{KeyCode, MouseButton};
+
+    use super::{KeyboardController, MouseController, key_code_from_name};
+
+    #[test]
+    fn test_key_repeats() {
+        let mut keyboard = KeyboardController::default();
+
+        keyboard.handle_keyboard_input(KeyCode::Backspace, true, false);
+
+        assert!(keyboard.is_key_pressed_once(KeyCode::Backspace));
+        assert!(!keyboard.is_key_repeated(KeyCode::Backspace));
+
+        keyboard.clear();
+        keyboard.handle_keyboard_input(KeyCode::Backspace, true, true);
+
+        assert!(keyboard.is_key_pressed(KeyCode::Backspace));
+        assert!(!keyboard.is_key_pressed_once(KeyCode::Backspace));
+        assert!(keyboard.is_key_repeated(KeyCode::Backspace));
+
+        keyboard.clear();
+        keyboard.handle_keyboard_input(KeyCode::Backspace, false, false);
+
+        assert!(!keyboard.is_key_pressed(KeyCode::Backspace));
+        assert!(!keyboard.is_key_repeated(KeyCode::Backspace));
+        assert!(keyboard.is_key_released(KeyCode::Backspace));
+
+        keyboard.ignore_repeats = true;
+        keyboard.clear();
+        keyboard.handle_keyboard_input(KeyCode::Backspace, true, false);
+        keyboard.handle_keyboard_input(KeyCode::Backspace, true, true);
+
+        assert!(keyboard.is_key_pressed_once(KeyCode::Backspace));
+        assert!(!keyboard.is_key_repeated(KeyCode::Backspace));
+    }
+
+    #[test]
+    fn test_drag_delta() {
+        let mut mouse = MouseController::default();
+        let now = Instant::now();
+
+        mouse.handle_mouse_motion(glam::Vec2::new(10.0, 10.0));
+        assert_eq!(mouse.drag_delta(), None);
+
+        mouse.handle_mouse_button_at(MouseButton::Left, true, now);
+        mouse.handle_mouse_motion(glam::Vec2::new(15.0, 12.0));
+        mouse.handle_mouse_motion(glam::Vec2::new(20.0, 10.0));
+
+        assert_eq!(mouse.drag_delta(), Some(glam::Vec2::new(10.0, 0.0)));
+
+        mouse.clear();
+
+        assert_eq!(mouse.drag_delta(), Some(glam::Vec2::ZERO));
+        assert_eq!(mouse.drag_origin(), Some(glam::Vec2::new(10.0, 10.0)));
+
+        mouse.handle_mouse_button_at(MouseButton::Left, false, now);
+
+        assert_eq!(mouse.drag_delta(), None);
+        assert_eq!(mouse.drag_origin(), None);
+    }
+
+    #[test]
+    fn test_double_click() {
+        let mut mouse = MouseController::default();
+        let now = Instant::now();
+        let click = |mouse: &mut MouseController, at: Duration| {
+            mouse.clear();
+            mouse.handle_mouse_button_at(MouseButton::Left, true, now + at);
+            mouse.handle_mouse_button_at(MouseButton::Left, false, now + at);
+        };
+
+        click(&mut mouse, Duration::ZERO);
+        assert!(!mouse.is_double_click(MouseButton::Left));
+
+        click(&mut mouse, Duration::from_millis(200));
+        assert!(mouse.is_double_click(MouseButton::Left));
+
+        click(&mut mouse, Duration::from_millis(300));
+        assert!(!mouse.is_double_click(MouseButton::Left));
+
+        click(&mut mouse, Duration::from_millis(1000));
+        assert!(!mouse.is_double_click(MouseButton::Left));
+        assert!(!mouse.is_double_click(MouseButton::Right));
+    }
+
+    #[test]
+    fn test_key_code_from_name() {
+        assert_eq!(key_code_from_name("KeyW"), Some(KeyCode::KeyW));
+        assert_eq!(key_code_from_name("ShiftLeft"), Some(KeyCode::ShiftLeft));
+        assert_eq!(key_code_from_name("Digit7"), Some(KeyCode::Digit7));
+        assert_eq!(key_code_from_name("keyw"), None);
+        assert_eq!(key_code_from_name("NotAKey"), None);
+    }
+}
@@ -8,6 +8,7 @@
     unused_crate_dependencies
 )]
 
+mod audio;
 mod blocks;
 mod camera;
 mod clock;
@@ -22,10 +23,10 @@ mod util;
 mod world;
 
 use std::{
-    f32,
+    f32, fs,
     path::PathBuf,
     sync::{Arc, mpsc},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use cpal::traits::HostTrait;
@@ -39,6 +40,7 @@ use mavelin_world::{BlockSource, ChunkManager};
 use tracing::info;
 
 use crate::{
+    audio::Audio,
     blocks::{
         AirBlock, BlueRoseBlock, BricksBlock, CobbleStoneBlock, DebugBlock, DirtBlock, GrassBlock, GreenGlassBlock, IceBlock, OakLeavesBlock, OakLogBlock,
         RoseBlock, SandBlock, SnowBlock, StoneBlock, StoneBricksBlock, TorchBlock, WaterBlock, WoodBlock,
@@ -48,7 +50,7 @@ use crate::{
     physics::AabbProvider,
     player::{Item, ItemType, Player},
     progress::{Progress, ProgressInfo, ProgressSender},
-    render::{common::CommonRenderer, context::UiContext},
+    render::{common::CommonRenderer, context::UiContext, texture_animation::TextureAnimations},
     scenes::{
         Screen,
         loading_overlay::LoadingOverlay,
@@ -62,6 +64,8 @@ use crate::{
 pub const TICK_RATE_MS: usize = 50;
 pub const TICK_RATE: Duration = Duration::from_millis(TICK_RATE_MS as u64);
 pub const TPS: usize = 1000 / TICK_RATE_MS;
+/// Frame rate limit while no world is loaded, the menus don't need more.
+pub const MENU_MAX_FPS: u32 = 60;
 pub const PHYSICS_RATE: Duration = Duration::from_secs(1).checked_div(60).expect("failed to calculate fixed framerate somehow");
 
 enum Action {
@@ -71,8 +75,7 @@ enum Action {
 }
 
 struct GameLoop {
-    #[allow(dead_code)]
-    audio_manager: AudioManager,
+    audio: Audio,
     input: Input,
     common_renderer: CommonRenderer,
     resource_manager: Arc<ResourceStorage>,
@@ -87,6 +90,7 @@ struct GameLoop {
     // kawase: DualKawase<4>,
     texture_atlas: wgpu::Texture,
     lightmap_atlas: wgpu::Texture,
+    texture_animations: TextureAnimations,
 
     context: UiContext,
     overlay: LoadingOverlay,
@@ -95,6 +99,7 @@ struct GameLoop {
 
     world: Option<World>,
     settings: Settings,
+    screenshot_requested: bool,
 }
 
 fn register_block<T: Block + 'static>(
@@ -110,20 +115,41 @@ fn register_block<T: Block + 'static>(
 }
 
 impl GameLoop {
+    fn save_settings(&self) {
+        if let Err(error) = self.settings.save(Settings::PATH) {
+            tracing::error!("{error}");
+        }
+    }
+
     #[profiling::function]
     fn handle_shortcuts(&mut self, context: WindowContext) {
+        if self.input.keyboard.is_key_pressed_once(KeyCode::F2) {
+            self.screenshot_requested = true;
+        }
+
         if self.input.keyboard.is_key_pressed_once(KeyCode::F3) {
             self.settings.debugging.enabled = !self.settings.debugging.enabled;
         }
 
+        if self.input.keyboard.is_key_pressed_once(KeyCode::F6) {
+            self.settings.debugging.ui_outlines = !self.settings.debugging.ui_outlines;
+        }
+
         if self.input.keyboard.is_key_pressed_once(KeyCode::F11) {
             context.toggle_fullscreen();
+
+            self.settings.graphics.fullscreen = !self.settings.graphics.fullscreen;
+            self.save_settings();
         }
 
         if self.input.keyboard.is_key_pressed_once(KeyCode::KeyL) {
             self.resource_manager.debug_save();
         }
 
+        if self.input.keyboard.is_key_pressed_once(KeyCode::KeyR) {
+            self.reload_resources(context.queue);
+        }
+
         if let Some(world) = &mut self.world {
             if self.input.keyboard.modifiers.control_key && self.input.keyboard.is_key_pressed_once(KeyCode::KeyS) {
                 info!("Saving world ({} chunks)", world.chunk_manager.len());
@@ -155,6 +181,18 @@ impl GameLoop {
             if self.input.keyboard.is_key_pressed_once(KeyCode::KeyM) {
                 world.marked = world.camera.looking_at.map(|looking_at| looking_at.position);
             }
+
+            if self.input.keyboard.is_key_pressed_once(KeyCode::KeyT) {
+                world.chunk_renderer.toggle_wireframe();
+            }
+
+            if self.input.keyboard.is_key_pressed_once(KeyCode::KeyO) {
+                world.camera.toggle_projection();
+            }
+
+            if self.input.keyboard.is_key_pressed_once(KeyCode::F5) {
+                world.camera.toggle_third_person();
+            }
         }
 
         if self.input.keyboard.modifiers.control_key {
@@ -162,6 +200,7 @@ impl GameLoop {
                 context.set_vsync(!self.settings.graphics.vsync);
 
                 self.settings.graphics.vsync = !self.settings.graphics.vsync;
+                self.save_settings();
             }
 
             if self.input.keyboard.is_key_pressed_once(KeyCode::KeyL) {
@@ -169,6 +208,31 @@ impl GameLoop {
             }
         }
     }
+
+    /// Picks up block models and textures edited on disk since they were
+    /// loaded, see [`ResourceStorage::reload`].
+    fn reload_resources(&mut self, queue: &wgpu::Queue) {
+        let reloaded = if let Some(world) = self.world.take() {
+            let (world, reloaded) = world.reload_resources(&mut self.resource_manager);
+
+            self.world = Some(world);
+
+            reloaded
+        } else {
+            Arc::get_mut(&mut self.resource_manager).map(ResourceStorage::reload)
+        };
+
+        match reloaded {
+            Some((models, textures_changed)) if textures_changed || !models.is_empty() => {
+                info!("Reloaded {} block models, textures changed: {textures_changed}", models.len());
+
+                progress::upload_atlases(queue, &self.texture_atlas, &self.lightmap_atlas, &self.resource_manager);
+                self.texture_animations.reset(&self.resource_manager);
+            }
+            Some(_) => info!("No resources changed"),
+            None => info!("Resources are in use by background jobs, try reloading again"),
+        }
+    }
 }
 
 impl State for GameLoop {
@@ -246,32 +310,41 @@ impl State for GameLoop {
             sender.set_visible(false)
         });
 
+        let settings = Settings::load_or_default(Settings::PATH);
+
+        context.set_vsync(settings.graphics.vsync);
+
+        if settings.graphics.fullscreen {
+            context.toggle_fullscreen();
+        }
+
         let size = context.window_size().as_vec2();
 
         let mut common_renderer = CommonRenderer::new(&context);
 
         common_renderer.add_font("default", include_bytes!("../../../resources/fonts/Monocraft.ttf"));
         common_renderer.add_font("default_bold", include_bytes!("../../../resources/fonts/Monocraft-Bold.ttf"));
+        common_renderer.set_round_rect_segments(settings.graphics.ui_corner_segments);
         common_renderer.set_window_matrix(
             context.queue,
             glam::camera::rh::proj::directx::orthographic(0.0, size.x, size.y, 0.0, -100.0, 100.0),
         );
 
-        // let sounds = fs::read_dir("./resources/sounds")
-        //     .unwrap()
-        //     .flatten()
-        //     .filter_map(|sound| {
-        //         let path = sound.path();
-
-        //         if path.is_file() {
-        //             StaticSoundData::from_file(path)
-        //                 .ok()
-        //                 .and_then(|data| Some((sound.file_name().into_string().ok()?,
-        // data)))         } else {
-        //             None
-        //         }
-        //     })
-        //     .collect();
+        let mut audio = Audio::new(
+            AudioManager::new(AudioManagerSettings {
+                backend_settings: CpalBackendSettings {
+                    device: cpal::host_from_id(cpal::HostId::Jack)
+                        .ok()
+                        .and_then(|host| host.default_output_device())
+                        .or_else(|| cpal::default_host().default_output_device()),
+                    ..CpalBackendSettings::default()
+                },
+                ..AudioManagerSettings::default()
+            })
+            .unwrap(),
+        );
+
+        audio.load_dir("./resources/sounds");
 
         let texture_descriptor = wgpu::TextureDescriptor {
             size: wgpu::Extent3d {
@@ -289,23 +362,8 @@ impl State for GameLoop {
         };
 
         Self {
-            audio_manager: AudioManager::new(AudioManagerSettings {
-                backend_settings: CpalBackendSettings {
-                    device: cpal::host_from_id(cpal::HostId::Jack)
-                        .ok()
-                        .and_then(|host| host.default_output_device())
-                        .or_else(|| cpal::default_host().default_output_device()),
-                    ..CpalBackendSettings::default()
-                },
-                ..AudioManagerSettings::default()
-            })
-            .unwrap(),
-            input: Input::with_binds([
-                ("walk.forward", KeyCode::KeyW),
-                ("walk.backward", KeyCode::KeyS),
-                ("walk.left", KeyCode::KeyA),
-                ("walk.right", KeyCode::KeyD),
-            ]),
+            audio,
+            input: Input::with_binds(settings.controls.key_binds()),
             common_renderer,
             current_page: Page::Main,
             resource_manager,
@@ -313,7 +371,8 @@ impl State for GameLoop {
             addons: mavelin_addons::AddonManager::new("./addons").unwrap(),
             action_receiver,
             world: None,
-            settings: Settings::default(),
+            settings,
+            screenshot_requested: false,
             progress: Progress::new(rx),
             texture_atlas: context.device.create_texture(&wgpu::TextureDescriptor {
                 label: Some("Texture Atlas"),
@@ -323,6 +382,7 @@ impl State for GameLoop {
                 label: Some("LightMap Atlas"),
                 ..texture_descriptor
             }),
+            texture_animations: TextureAnimations::default(),
             // scene: WorldScene::new(backend, width, height).unwrap(),
             // kawase: DualKawase::new(backend, width, height).unwrap(),
             context: UiContext::new(),
@@ -372,7 +432,7 @@ impl State for GameLoop {
                     entity_manager: &world.entities,
                     storage: self.resource_manager.as_ref(),
                 }),
-                world.player.handle_mouse(delta),
+                world.player.handle_mouse(delta * self.settings.controls.mouse_sensitivity),
             );
         } else if let Some(position) = position {
             self.input.mouse.handle_mouse_motion(position);
@@ -395,8 +455,11 @@ impl State for GameLoop {
     fn update(&mut self, context: WindowContext, delta: Duration) {
         self.handle_shortcuts(context);
 
+        context.set_max_fps(if self.world.is_some() { None } else { Some(MENU_MAX_FPS) });
+
         if let Some(world) = &mut self.world {
-            world.update(&context, self.settings.graphics, &self.input, delta);
+            world.player.set_step_onto_ledges(self.settings.controls.step_onto_ledges);
+            world.update(&context, self.settings.graphics, &self.input, &mut self.audio, delta);
 
             for (_, drop) in &mut world.entities {
                 if let EntityData::Item { transition, .. } = &mut drop.data {
@@ -408,6 +471,8 @@ impl State for GameLoop {
         self.overlay.update(delta);
         self.progress
             .update(context.queue, &self.texture_atlas, &self.lightmap_atlas, &self.resource_manager);
+        self.texture_animations
+            .update(context.queue, &self.texture_atlas, &self.resource_manager, delta);
         self.context.update();
 
         if self.input.mouse.is_released(MouseButton::Left) {
@@ -422,7 +487,10 @@ impl State for GameLoop {
 
         if let Ok(action) = self.action_receiver.try_recv() {
             match action {
-                Action::ReplaceResourceManager(manager) => self.resource_manager = Arc::new(manager),
+                Action::ReplaceResourceManager(manager) => {
+                    self.texture_animations.reset(&manager);
+                    self.resource_manager = Arc::new(manager);
+                }
                 #[cfg(feature = "addons")]
                 Action::ReplaceAddonManager(addons) => self.addons = addons,
             }
@@ -501,11 +569,12 @@ impl State for GameLoop {
                     // pass.clear_color_and_depth(Color::from_u32_rgb(0x1D211B).as_value(), 1.0);
 
                     let mut root = self.context.root(&self.common_renderer, context.window_size().as_vec2());
+                    let mut previous_world = None;
 
                     if matches!(self.current_page, Page::Main) {
                         match MainScreen.render(&mut root) {
                             Some(MainScreenAction::StartGame) => {
-                                self.world.replace(apply_world_template(
+                                previous_world = self.world.replace(apply_world_template(
                                     World::new(
                                         &context,
                                         &self.texture_atlas,
@@ -529,8 +598,16 @@ impl State for GameLoop {
 
                     drop(root);
 
+                    if let Some(mut world) = previous_world {
+                        world.minimap.release(&mut self.common_renderer);
+                    }
+
                     self.context.paint_root(&mut self.common_renderer, context.queue);
 
+                    if self.settings.debugging.ui_outlines {
+                        self.context.paint_debug_outlines(&mut self.common_renderer);
+                    }
+
                     _ = self.common_renderer.render(&mut pass, &context);
                 }
             }
@@ -538,6 +615,15 @@ impl State for GameLoop {
             let buffer = encoder.finish();
 
             context.queue.submit([buffer]);
+
+            if std::mem::take(&mut self.screenshot_requested) {
+                if let Some(image) = mavelin_engine::read_texture_to_image(context.device, context.queue, &output.texture) {
+                    save_screenshot(image);
+                } else {
+                    tracing::warn!("Screenshots aren't supported by this surface ({:?})", output.texture.format());
+                }
+            }
+
             context.pre_present_notify();
             context.queue.present(output);
         }
@@ -579,12 +665,28 @@ impl State for GameLoop {
 
         self.settings.debugging.draw_calls_stat.push_back(info.draw_calls);
         self.settings.debugging.draw_calls_max = self.settings.debugging.draw_calls_max.max(info.draw_calls);
+        self.settings.debugging.render_stats.push(info, delta);
         self.settings.debugging.render_info = info;
 
         profiling::finish_frame!();
     }
 }
 
+fn save_screenshot(image: image::RgbaImage) {
+    std::thread::spawn(move || {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let path = PathBuf::from("./screenshots").join(format!("screenshot-{timestamp}.png"));
+
+        if let Err(error) = fs::create_dir_all("./screenshots") {
+            tracing::error!("Failed to create screenshots directory: {error}");
+        } else if let Err(error) = image.save(&path) {
+            tracing::error!("Failed to save screenshot: {error}");
+        } else {
+            info!("Saved screenshot to {}", path.display());
+        }
+    });
+}
+
 #[allow(dead_code)]
 enum Page {
     Options,
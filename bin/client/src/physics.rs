@@ -32,7 +32,7 @@ impl<C: ChunkCache> AabbSource for AabbProvider<'_, C> {
         }
 
         if let Some(block) = self.chunk_manager.get_block(correct_position.as_ivec3())
-            && self.storage.blocks.get_unchecked(block.id).collidable()
+            && self.storage.blocks.get_unchecked(block.id).is_solid()
         {
             let block_pos = position.as_dvec3();
 
@@ -72,7 +72,7 @@ impl<C: ChunkCache> AabbSource for LimitedAabbProvider<'_, C> {
         let correct_position = position.floor();
 
         if let Some(block) = self.chunk_manager.get_block(correct_position.as_ivec3())
-            && self.storage.blocks.get_unchecked(block.id).collidable()
+            && self.storage.blocks.get_unchecked(block.id).is_solid()
         {
             let block_pos = position.as_dvec3();
 
@@ -2,10 +2,9 @@ use std::f32;
 
 use ahash::HashMap;
 use mavelin_engine::KeyCode;
-use mavelin_physics::{Aabb, PhysicsBody};
-use mavelin_shared::Lerp;
+use mavelin_physics::{Aabb, PhysicsBody, PhysicsConfig};
 
-use crate::{Camera, get_movement_direction, get_rotation_directions, input::Input};
+use crate::{get_movement_direction, get_rotation_directions, input::Input};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ItemType {
@@ -126,6 +125,37 @@ impl Inventory {
     }
 }
 
+/// How the player moves through the world.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MovementMode {
+    /// Affected by gravity and blocked by solid blocks.
+    #[default]
+    Walking,
+    /// Ignores gravity, ascends with space and descends with shift.
+    Flying,
+    /// Same as [`MovementMode::Flying`], but also passes through blocks.
+    NoClip,
+}
+
+impl MovementMode {
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Walking => Self::Flying,
+            Self::Flying => Self::NoClip,
+            Self::NoClip => Self::Walking,
+        }
+    }
+
+    pub const fn is_flying(self) -> bool {
+        matches!(self, Self::Flying | Self::NoClip)
+    }
+
+    pub const fn has_collision(self) -> bool {
+        !matches!(self, Self::NoClip)
+    }
+}
+
 pub struct Player {
     // START CAMERA
     pub yaw: f32,
@@ -133,6 +163,8 @@ pub struct Player {
     // END CAMERA
     // START PHYSICS
     pub body: PhysicsBody,
+    pub movement_mode: MovementMode,
+    pub sprinting: bool,
     // END PHYSICS
     // CAMERA BOBBING START
     pub bob_time: f32,
@@ -145,10 +177,14 @@ pub struct Player {
 
 impl Default for Player {
     fn default() -> Self {
+        let body = PhysicsBody::new(glam::Vec3::Y, Self::PLAYER_SIZE.as_vec3());
+
         Self {
             yaw: 0.0,
             pitch: 0.0,
-            body: PhysicsBody::new(glam::Vec3::Y, Self::PLAYER_SIZE.as_vec3()),
+            body,
+            movement_mode: MovementMode::Walking,
+            sprinting: false,
             bob_time: 0.0,
             bob_offset: glam::Vec3::ZERO,
             dash_time: 0.0,
@@ -166,11 +202,27 @@ impl Player {
         glam::Vec3::ZERO
     };
     pub const IS_THIRD_PERSON: bool = false;
+    /// Step height used when walking onto full blocks without jumping is
+    /// enabled, see [`Self::set_step_onto_ledges`].
+    pub const LEDGE_STEP_HEIGHT: f32 = 1.0;
     pub const LOOK_SPEED: f32 = 0.1;
     pub const MOUSE_SENSE: f32 = 0.05;
     pub const MOVE_SPEED: f32 = 4.0;
     pub const PLAYER_HALF_SIZE: glam::DVec3 = glam::DVec3::new(0.35 / 2.0, 1.625 / 2.0, 0.35 / 2.0);
     pub const PLAYER_SIZE: glam::DVec3 = glam::DVec3::new(0.35, 1.625, 0.35);
+    pub const SPRINT_FOV_SCALE: f32 = 65.0 / 55.0;
+    pub const SPRINT_MULTIPLIER: f32 = 1.5;
+
+    /// Lets the player walk onto full-block ledges without jumping. Otherwise
+    /// only the default step height of [`PhysicsConfig`] is climbed, which
+    /// stays below a full block.
+    pub fn set_step_onto_ledges(&mut self, enabled: bool) {
+        self.body.config.step_height = if enabled {
+            Self::LEDGE_STEP_HEIGHT
+        } else {
+            PhysicsConfig::default().step_height
+        };
+    }
 
     #[inline]
     pub fn calc_player_aabb(position: glam::Vec3) -> Aabb {
@@ -202,6 +254,14 @@ impl Player {
         const DASH_SPEED: f32 = 30.0;
         const DASH_DURATION: f32 = 0.2;
 
+        if input.keyboard.is_key_pressed_once(KeyCode::KeyF) {
+            self.set_movement_mode(self.movement_mode.next());
+        }
+
+        if self.movement_mode.is_flying() {
+            return;
+        }
+
         if input.keyboard.is_key_pressed_once(KeyCode::KeyE) && !self.body.is_on_ground {
             let (front, _right, _) = get_rotation_directions(self.yaw, self.pitch);
 
@@ -213,11 +273,40 @@ impl Player {
         }
     }
 
-    pub fn physics_step(&mut self, input: &Input, camera: &mut Camera, delta: f32) {
+    #[inline]
+    pub fn movement_speed(&self) -> f32 {
+        if self.sprinting {
+            Self::MOVE_SPEED * Self::SPRINT_MULTIPLIER
+        } else {
+            Self::MOVE_SPEED
+        }
+    }
+
+    pub fn set_movement_mode(&mut self, mode: MovementMode) {
+        self.movement_mode = mode;
+        self.body.config.gravity_scale = if mode.is_flying() { 0.0 } else { 1.0 };
+        self.body.velocity.y = 0.0;
+    }
+
+    pub fn physics_step(&mut self, input: &Input, delta: f32) {
         const BOB_SPEED: f32 = 3.0;
         const BOB_FREQ: f32 = 2.0;
         const BOB_AMP: f32 = 0.1;
 
+        let direction = get_movement_direction(input);
+        let flying = self.movement_mode.is_flying();
+        // Shift descends while flying, and Ctrl is taken by the shortcuts in
+        // `GameLoop::handle_shortcuts` (Ctrl+S would save while moving back).
+        let sprint_key = if flying { KeyCode::KeyQ } else { KeyCode::ShiftLeft };
+
+        // Sprinting can only be started on the ground, but carries over into
+        // jumps until the player stops moving forward.
+        if direction.z <= 0.0 {
+            self.sprinting = false;
+        } else if input.keyboard.is_key_pressed(sprint_key) && (self.body.is_on_ground || flying) {
+            self.sprinting = true;
+        }
+
         let was_dashing = self.dash_time > 0.0;
 
         if was_dashing {
@@ -240,7 +329,7 @@ impl Player {
             let mut amp = BOB_AMP;
             let mut freq = BOB_FREQ;
 
-            if input.keyboard.is_key_pressed(KeyCode::ShiftLeft) {
+            if self.sprinting {
                 amp *= 1.5;
                 freq *= 1.5;
             }
@@ -249,28 +338,23 @@ impl Player {
             self.bob_offset = glam::Vec3::new(amp * (self.bob_time * freq).sin(), amp * (self.bob_time * freq * 2.0).sin(), 0.0);
         }
 
-        let direction = get_movement_direction(input);
-
         let (front, right, _) = get_rotation_directions(self.yaw, 0.0);
 
-        let velocity = (front * direction.z + right * direction.x)
-            * if input.keyboard.is_key_pressed(KeyCode::ShiftLeft) && direction.z > 0.0 {
-                camera.fov = camera.fov.lerp(
-                    &(65f32.to_radians() * (self.body.velocity.y.abs() / 8.0).clamp(1.0, 1.75)),
-                    (delta * 16.0).min(1.0),
-                );
+        let velocity = (front * direction.z + right * direction.x) * self.movement_speed();
 
-                Self::MOVE_SPEED * 1.5
-            } else {
-                camera.fov = camera.fov.lerp(
-                    &(55f32.to_radians() * (self.body.velocity.y.abs() / 8.0).clamp(1.0, 1.75)),
-                    (delta * 16.0).min(1.0),
-                );
+        if flying {
+            let mut vertical = 0.0;
 
-                Self::MOVE_SPEED
-            };
+            if input.keyboard.is_key_pressed(KeyCode::Space) {
+                vertical += 1.0;
+            }
+
+            if input.keyboard.is_key_pressed(KeyCode::ShiftLeft) {
+                vertical -= 1.0;
+            }
 
-        if self.body.is_on_ground {
+            self.body.velocity = glam::Vec3::new(velocity.x, vertical * Self::MOVE_SPEED * 1.5, velocity.z);
+        } else if self.body.is_on_ground {
             self.body.velocity.x = velocity.x;
             self.body.velocity.z = velocity.z;
         } else {
@@ -100,49 +100,7 @@ impl Progress {
                     }
 
                     if !visible {
-                        for (mipmap, image) in resource_manager.get_mipmaps().iter().enumerate() {
-                            queue.write_texture(
-                                wgpu::TexelCopyTextureInfoBase {
-                                    texture,
-                                    mip_level: mipmap as u32,
-                                    origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
-                                    aspect: wgpu::TextureAspect::All,
-                                },
-                                image.as_raw(),
-                                wgpu::TexelCopyBufferLayout {
-                                    offset: 0,
-                                    bytes_per_row: Some(4 * image.width()),
-                                    rows_per_image: Some(image.height()),
-                                },
-                                wgpu::Extent3d {
-                                    width: image.width(),
-                                    height: image.height(),
-                                    depth_or_array_layers: 1,
-                                },
-                            );
-                        }
-
-                        for (mipmap, image) in resource_manager.get_lightmap_mipmaps().iter().enumerate() {
-                            queue.write_texture(
-                                wgpu::TexelCopyTextureInfoBase {
-                                    texture: lightmap,
-                                    mip_level: mipmap as u32,
-                                    origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
-                                    aspect: wgpu::TextureAspect::All,
-                                },
-                                image.as_raw(),
-                                wgpu::TexelCopyBufferLayout {
-                                    offset: 0,
-                                    bytes_per_row: Some(4 * image.width()),
-                                    rows_per_image: Some(image.height()),
-                                },
-                                wgpu::Extent3d {
-                                    width: image.width(),
-                                    height: image.height(),
-                                    depth_or_array_layers: 1,
-                                },
-                            );
-                        }
+                        upload_atlases(queue, texture, lightmap, resource_manager);
                     }
                 }
             }
@@ -173,3 +131,51 @@ impl ProgressSender {
         self.0.send(ProgressChange::SetVisible(visible))
     }
 }
+
+/// Copies every mip level of the block texture and lightmap atlases to the
+/// GPU.
+pub fn upload_atlases(queue: &wgpu::Queue, texture: &wgpu::Texture, lightmap: &wgpu::Texture, resource_manager: &ResourceStorage) {
+    for (mipmap, image) in resource_manager.get_mipmaps().iter().enumerate() {
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfoBase {
+                texture,
+                mip_level: mipmap as u32,
+                origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            image.as_raw(),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * image.width()),
+                rows_per_image: Some(image.height()),
+            },
+            wgpu::Extent3d {
+                width: image.width(),
+                height: image.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    for (mipmap, image) in resource_manager.get_lightmap_mipmaps().iter().enumerate() {
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfoBase {
+                texture: lightmap,
+                mip_level: mipmap as u32,
+                origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            image.as_raw(),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * image.width()),
+                rows_per_image: Some(image.height()),
+            },
+            wgpu::Extent3d {
+                width: image.width(),
+                height: image.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
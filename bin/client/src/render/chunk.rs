@@ -1,5 +1,10 @@
-use std::{borrow::Borrow, hash::Hash};
+use std::{
+    borrow::Borrow,
+    hash::Hash,
+    time::{Duration, Instant},
+};
 
+use ahash::HashMap;
 use indexmap::IndexMap;
 use mavelin_engine::WindowContext;
 use mavelin_shared::{AsValue, Color, Cube, Face, Frustum};
@@ -72,41 +77,70 @@ impl TranslucentSubchunk {
     fn update(&mut self, device: &wgpu::Device, last_pos: glam::Vec3, origin: glam::IVec2) {
         if self.last_pos.distance_squared(last_pos) > 2.0 && !self.faces.is_empty() {
             Self::resort_faces(&mut self.faces, last_pos, origin);
+            self.write_faces(device);
 
-            let mut builder = VoxelMeshBuilder::new();
+            self.last_pos = last_pos;
+        }
+    }
 
-            builder.extend_from_slice(&self.faces);
+    /// Swaps the faces for a freshly meshed set, writing them into the
+    /// existing buffers if they fit.
+    fn replace(&mut self, device: &wgpu::Device, mut faces: Vec<VoxelFace>, last_pos: glam::Vec3, origin: glam::IVec2) {
+        Self::resort_faces(&mut faces, last_pos, origin);
 
-            {
-                self.buffer.vertices.slice(..).map_async(wgpu::MapMode::Write, |_| ());
+        let (vertices, indices) = (faces.len() * 4 * size_of::<VoxelVertex>(), faces.len() * 6 * size_of::<u32>());
+
+        if vertices as u64 > self.buffer.vertices.size() || indices as u64 > self.buffer.indices.size() {
+            *self = Self::new(device, faces, last_pos, origin);
+        } else {
+            self.faces = faces;
+            self.last_pos = last_pos;
+
+            if self.faces.is_empty() {
+                self.buffer.count = 0;
+            } else {
+                self.write_faces(device);
+            }
+        }
+    }
 
-                device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    fn write_faces(&mut self, device: &wgpu::Device) {
+        let mut builder = VoxelMeshBuilder::new();
 
-                {
-                    let mut buffer = self.buffer.vertices.slice(..).get_mapped_range_mut().unwrap();
+        builder.extend_from_slice(&self.faces);
 
-                    buffer.copy_from_slice(bytemuck::cast_slice(&builder.vertices));
-                }
+        let vertices: &[u8] = bytemuck::cast_slice(&builder.vertices);
+        let indices: &[u8] = bytemuck::cast_slice(&builder.indices);
 
-                self.buffer.vertices.unmap();
-            }
+        {
+            self.buffer.vertices.slice(..vertices.len() as u64).map_async(wgpu::MapMode::Write, |_| ());
+
+            device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
 
             {
-                self.buffer.indices.slice(..).map_async(wgpu::MapMode::Write, |_| ());
+                let mut buffer = self.buffer.vertices.slice(..vertices.len() as u64).get_mapped_range_mut().unwrap();
 
-                device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+                buffer.copy_from_slice(vertices);
+            }
 
-                {
-                    let mut buffer = self.buffer.indices.slice(..).get_mapped_range_mut().unwrap();
+            self.buffer.vertices.unmap();
+        }
 
-                    buffer.copy_from_slice(bytemuck::cast_slice(&builder.indices));
-                }
+        {
+            self.buffer.indices.slice(..indices.len() as u64).map_async(wgpu::MapMode::Write, |_| ());
+
+            device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+
+            {
+                let mut buffer = self.buffer.indices.slice(..indices.len() as u64).get_mapped_range_mut().unwrap();
 
-                self.buffer.indices.unmap();
+                buffer.copy_from_slice(indices);
             }
 
-            self.last_pos = last_pos;
+            self.buffer.indices.unmap();
         }
+
+        self.buffer.count = builder.indices.len();
     }
 
     fn resort_faces(faces: &mut [VoxelFace], last_pos: glam::Vec3, origin: glam::IVec2) {
@@ -126,6 +160,12 @@ struct RenderSubchunk {
     translucent: TranslucentSubchunk,
 }
 
+impl RenderSubchunk {
+    const fn is_empty(&self) -> bool {
+        self.solid.count == 0 && self.translucent.buffer.count == 0
+    }
+}
+
 pub struct VoxelMeshBuilder {
     vertices: Vec<VoxelVertex>,
     indices: Vec<u32>,
@@ -252,21 +292,27 @@ impl VoxelMeshBuilder {
 pub struct ChunkRenderer {
     solid_render_pipeline: wgpu::RenderPipeline,
     translucent_render_pipeline: wgpu::RenderPipeline,
+    wireframe_render_pipeline: wgpu::RenderPipeline,
     cloud_render_pipeline: wgpu::RenderPipeline,
     fog_bind_group: wgpu::BindGroup,
 
     fragment_bind_group: wgpu::BindGroup,
     fog: FogUniform,
     fog_buffer: wgpu::Buffer,
+    wireframe: WireframeUniform,
+    wireframe_buffer: wgpu::Buffer,
+    wireframe_enabled: bool,
 
     cloud_buffer: wgpu::Buffer,
     cloud_indices_buffer: wgpu::Buffer,
     cloud_indices_count: usize,
 
     subchunks: IndexMap<(glam::IVec2, usize), RenderSubchunk>,
+    /// When each chunk in range got its first mesh, used to fade it in.
+    chunk_loaded_at: HashMap<glam::IVec2, Instant>,
+    rendered_subchunks: usize,
     last_position: glam::IVec3,
     sun_position: f32,
-    fog_color: Color,
 }
 
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
@@ -281,13 +327,37 @@ struct FogUniform {
     _pad: [u32; 3],
 }
 
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct WireframeUniform {
+    color: [f32; 4],
+    line_width: f32,
+    _pad: [u32; 3],
+}
+
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
 struct VoxelImmediates {
     matrix: glam::Mat4,
     chunk: [f32; 3],
+    alpha: f32,
     sun_position: [f32; 3],
-    _pad: [u32; 2],
+    _pad: u32,
+}
+
+impl VoxelImmediates {
+    const ALPHA_OFFSET: u32 = 76;
+    const CHUNK_OFFSET: u32 = 64;
+
+    const fn new(matrix: glam::Mat4, sun_position: f32) -> Self {
+        Self {
+            matrix,
+            chunk: [0.0; 3],
+            alpha: 1.0,
+            sun_position: [0.0, sun_position, 0.0],
+            _pad: 0,
+        }
+    }
 }
 
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
@@ -299,6 +369,9 @@ struct CloudImmediates {
 }
 
 impl ChunkRenderer {
+    /// How long a freshly loaded chunk takes to fade in.
+    const FADE_IN_DURATION: Duration = Duration::from_millis(400);
+
     #[inline]
     #[allow(clippy::too_many_lines)]
     pub fn new(context: &WindowContext, texture: &wgpu::Texture, lightmap: &wgpu::Texture) -> Self {
@@ -343,6 +416,18 @@ impl ChunkRenderer {
             contents: bytemuck::bytes_of(&fog),
         });
 
+        let wireframe = WireframeUniform {
+            color: Color::WHITE.to_linear_rgba(),
+            line_width: 1.0,
+            _pad: [0; 3],
+        };
+
+        let wireframe_buffer = context.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Wireframe Buffer"),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            contents: bytemuck::bytes_of(&wireframe),
+        });
+
         let cloud_buffer = context.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Cloud Buffer: Vertices"),
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
@@ -358,25 +443,43 @@ impl ChunkRenderer {
         });
 
         let fog_bind_group_layout = context.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
                 },
-                count: None,
-            }],
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
             label: Some("Chunk Renderer Bind Group Layout"),
         });
 
         let fog_bind_group = context.device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &fog_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: fog_buffer.as_entire_binding(),
-            }],
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: fog_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wireframe_buffer.as_entire_binding(),
+                },
+            ],
             label: Some("Chunk Renderer Bind Group"),
         });
 
@@ -541,6 +644,44 @@ impl ChunkRenderer {
             cache: None,
         });
 
+        let wireframe_render_pipeline = context.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Chunk Renderer Wireframe Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Some(VoxelVertex::LAYOUT)],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_wireframe"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: *context.surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                cull_mode: Some(wgpu::Face::Back),
+                ..wgpu::PrimitiveState::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: mavelin_engine::Texture::DEPTH_FORMAT,
+                depth_write_enabled: Some(false),
+                depth_compare: Some(wgpu::CompareFunction::LessEqual),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview_mask: None,
+            cache: None,
+        });
+
         let cloud_render_pipeline = context.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Cloud Renderer Pipeline"),
             layout: Some(&cloud_render_pipeline_layout),
@@ -593,17 +734,22 @@ impl ChunkRenderer {
         Self {
             solid_render_pipeline,
             translucent_render_pipeline,
+            wireframe_render_pipeline,
             cloud_render_pipeline,
             fragment_bind_group,
             fog_bind_group,
             fog,
             fog_buffer,
+            wireframe,
+            wireframe_buffer,
+            wireframe_enabled: false,
             cloud_buffer,
             cloud_indices_buffer,
             cloud_indices_count: 0,
             subchunks: IndexMap::new(),
+            chunk_loaded_at: HashMap::default(),
+            rendered_subchunks: 0,
             last_position: glam::IVec3::ZERO,
-            fog_color: Color::BLACK,
             sun_position: 0.0,
         }
     }
@@ -655,21 +801,102 @@ impl ChunkRenderer {
         self.subchunks.insert(origin, RenderSubchunk { solid, translucent });
     }
 
+    /// Replaces the mesh of a subchunk after it was remeshed. Its GPU buffers
+    /// are reused when the new mesh fits into them, subchunks that weren't
+    /// uploaded yet get new ones. Empty meshes aren't uploaded at all and
+    /// drop the buffers of whatever was there before.
+    pub fn update_subchunk(
+        &mut self,
+        context: &WindowContext,
+        key: (glam::IVec2, usize),
+        solid: &[VoxelFace],
+        translucent: Vec<VoxelFace>,
+        camera_pos: glam::Vec3,
+    ) {
+        if solid.is_empty() && translucent.is_empty() {
+            self.subchunks.shift_remove(&key);
+        } else if let Some(subchunk) = self.subchunks.get_mut(&key) {
+            let mut builder = VoxelMeshBuilder::with_capacity(solid.len());
+
+            builder.extend_from_slice(solid);
+
+            subchunk.solid.replace(
+                context.device,
+                context.queue,
+                &builder.vertices,
+                "Solid SubChunk: Vertices",
+                &builder.indices,
+                "Solid SubChunk: Indices",
+            );
+
+            subchunk.translucent.replace(context.device, translucent, camera_pos, key.0);
+        } else {
+            let solid = VoxelMeshBuilder::build_from_slice(context.device, solid, "Solid SubChunk: Vertices", "Solid SubChunk: Indices");
+            let translucent = TranslucentSubchunk::new(context.device, translucent, camera_pos, key.0);
+
+            self.chunk_loaded_at.entry(key.0).or_insert_with(Instant::now);
+            self.set_subchunk(key, solid, translucent);
+        }
+    }
+
+    /// Opacity of a chunk that got its first mesh at `loaded_at`, ramping
+    /// from 0 to 1 over [`Self::FADE_IN_DURATION`].
+    fn fade_alpha(loaded_at: Option<&Instant>, now: Instant) -> f32 {
+        loaded_at.map_or(1.0, |&loaded_at| {
+            (now.saturating_duration_since(loaded_at).as_secs_f32() / Self::FADE_IN_DURATION.as_secs_f32()).min(1.0)
+        })
+    }
+
+    /// Number of subchunks drawn by the last [`Self::render`] call.
+    #[inline]
+    pub const fn rendered_subchunks(&self) -> usize {
+        self.rendered_subchunks
+    }
+
+    /// Number of uploaded subchunks that have anything to draw.
+    #[inline]
+    pub fn meshed_subchunks(&self) -> usize {
+        self.subchunks.values().filter(|subchunk| !subchunk.is_empty()).count()
+    }
+
     #[inline]
     pub const fn set_sun_position(&mut self, value: f32) {
         self.sun_position = value;
     }
 
+    /// Fades chunks into `color` between `start` and `end` blocks away from
+    /// the camera, hiding where the render distance ends.
     #[inline]
-    pub fn set_fog_color(&mut self, queue: &wgpu::Queue, value: Color) {
-        if self.fog_color != value {
-            self.fog_color = value;
-            self.fog.fog_color = value.to_linear_rgba();
+    pub fn set_fog(&mut self, queue: &wgpu::Queue, start: f32, end: f32, color: Color) {
+        let fog = FogUniform {
+            fog_color: color.to_linear_rgba(),
+            fog_render_dist_start: start,
+            fog_render_dist_end: end.max(start),
+            ..self.fog
+        };
+
+        if bytemuck::bytes_of(&fog) != bytemuck::bytes_of(&self.fog) {
+            self.fog = fog;
 
             queue.write_buffer(&self.fog_buffer, 0, bytemuck::bytes_of(&self.fog));
         }
     }
 
+    #[inline]
+    pub const fn toggle_wireframe(&mut self) {
+        self.wireframe_enabled = !self.wireframe_enabled;
+    }
+
+    /// Changes how the debug wireframe looks, `line_width` is in pixels.
+    #[inline]
+    #[allow(dead_code)]
+    pub fn set_wireframe_style(&mut self, queue: &wgpu::Queue, color: Color, line_width: f32) {
+        self.wireframe.color = color.to_linear_rgba();
+        self.wireframe.line_width = line_width.max(0.0);
+
+        queue.write_buffer(&self.wireframe_buffer, 0, bytemuck::bytes_of(&self.wireframe));
+    }
+
     #[inline]
     fn is_subchunk_visible<T: Frustum>(frustum: &T, (origin, subchunk): (glam::IVec2, usize)) -> bool {
         let origin = origin.as_vec2() * SUBCHUNK_SIZE_F32;
@@ -699,12 +926,10 @@ impl ChunkRenderer {
         render_pass.insert_debug_marker("render_buffer");
         render_pass.set_immediates(
             0,
-            bytemuck::bytes_of(&VoxelImmediates {
-                chunk: [0.0; 3],
+            bytemuck::bytes_of(&VoxelImmediates::new(
                 matrix,
-                sun_position: [0.0, if full_bright { const { (1.0 - 0.5) / 0.96 } } else { self.sun_position }, 0.0],
-                _pad: [0; 2],
-            }),
+                if full_bright { const { (1.0 - 0.5) / 0.96 } } else { self.sun_position },
+            )),
         );
 
         render_pass.draw_indexed(0..buffer.count as u32, 0, 0..1);
@@ -717,6 +942,7 @@ impl ChunkRenderer {
 
     #[profiling::function]
     pub fn filter_by_shape(&mut self, center: glam::IVec2, shape: RenderShape) -> impl Iterator<Item = glam::IVec2> {
+        self.chunk_loaded_at.retain(|&origin, _| shape.test(center, origin));
         self.subchunks
             .extract_if(.., move |&(origin, _), _| !shape.test(center, origin))
             .map(|((k, _), _)| k)
@@ -753,25 +979,34 @@ impl ChunkRenderer {
 
         let mut render_info = RenderInfo::default();
 
+        self.rendered_subchunks = self
+            .subchunks
+            .iter()
+            .filter(|&(&key, subchunk)| !subchunk.is_empty() && Self::is_subchunk_visible(frustum, key))
+            .count();
+
         render_pass.set_pipeline(&self.solid_render_pipeline);
         render_pass.set_bind_group(0, &self.fragment_bind_group, &[]);
         render_pass.set_bind_group(1, &self.fog_bind_group, &[]);
-        render_pass.set_immediates(
-            0,
-            bytemuck::bytes_of(&VoxelImmediates {
-                chunk: [0.0; 3],
-                matrix,
-                sun_position: [0.0, self.sun_position, 0.0],
-                _pad: [0; 2],
-            }),
-        );
+        render_pass.set_immediates(0, bytemuck::bytes_of(&VoxelImmediates::new(matrix, self.sun_position)));
+
+        let now = Instant::now();
+        let mut fading = Vec::new();
 
         for (&key, subchunk) in &self.subchunks {
             if Self::is_subchunk_visible(frustum, key) && subchunk.solid.count > 0 {
+                let alpha = Self::fade_alpha(self.chunk_loaded_at.get(&key.0), now);
+
+                if alpha < 1.0 {
+                    fading.push((key, subchunk, alpha));
+
+                    continue;
+                }
+
                 let chunk_origin = glam::IVec3::new(key.0.x, 0, key.0.y) * SUBCHUNK_SIZE_I32;
                 let chunk_offset = chunk_origin.as_vec3() - camera_pos;
 
-                render_pass.set_immediates(64, bytemuck::bytes_of(&chunk_offset.to_array()));
+                render_pass.set_immediates(VoxelImmediates::CHUNK_OFFSET, bytemuck::bytes_of(&chunk_offset.to_array()));
                 render_pass.set_vertex_buffer(0, subchunk.solid.vertices.slice(..));
                 render_pass.set_index_buffer(subchunk.solid.indices.slice(..), wgpu::IndexFormat::Uint32);
                 render_pass.draw_indexed(0..subchunk.solid.count as u32, 0, 0..1);
@@ -780,23 +1015,34 @@ impl ChunkRenderer {
             }
         }
 
+        // Fading chunks go after all opaque ones and back to front, so that
+        // whatever is behind them is already there to blend with.
+        for (key, subchunk, alpha) in fading.into_iter().rev() {
+            let chunk_origin = glam::IVec3::new(key.0.x, 0, key.0.y) * SUBCHUNK_SIZE_I32;
+            let chunk_offset = chunk_origin.as_vec3() - camera_pos;
+
+            render_pass.set_immediates(VoxelImmediates::CHUNK_OFFSET, bytemuck::bytes_of(&chunk_offset.to_array()));
+            render_pass.set_immediates(VoxelImmediates::ALPHA_OFFSET, bytemuck::bytes_of(&alpha));
+            render_pass.set_vertex_buffer(0, subchunk.solid.vertices.slice(..));
+            render_pass.set_index_buffer(subchunk.solid.indices.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..subchunk.solid.count as u32, 0, 0..1);
+
+            render_info.draw_calls += 1;
+        }
+
         render_pass.set_pipeline(&self.translucent_render_pipeline);
-        render_pass.set_immediates(
-            0,
-            bytemuck::bytes_of(&VoxelImmediates {
-                chunk: [0.0; 3],
-                matrix,
-                sun_position: [0.0, self.sun_position, 0.0],
-                _pad: [0; 2],
-            }),
-        );
+        render_pass.set_immediates(0, bytemuck::bytes_of(&VoxelImmediates::new(matrix, self.sun_position)));
 
         for (&key, subchunk) in self.subchunks.iter_mut().rev() {
             if Self::is_subchunk_visible(frustum, key) && subchunk.translucent.buffer.count > 0 {
                 let chunk_origin = glam::IVec3::new(key.0.x, 0, key.0.y) * SUBCHUNK_SIZE_I32;
                 let chunk_offset = chunk_origin.as_vec3() - camera_pos;
 
-                render_pass.set_immediates(64, bytemuck::bytes_of(&chunk_offset.to_array()));
+                render_pass.set_immediates(VoxelImmediates::CHUNK_OFFSET, bytemuck::bytes_of(&chunk_offset.to_array()));
+
+                let alpha = Self::fade_alpha(self.chunk_loaded_at.get(&key.0), now);
+
+                render_pass.set_immediates(VoxelImmediates::ALPHA_OFFSET, bytemuck::bytes_of(&alpha));
 
                 subchunk.translucent.update(device, camera_pos, key.0);
 
@@ -808,6 +1054,30 @@ impl ChunkRenderer {
             }
         }
 
+        if self.wireframe_enabled {
+            render_pass.set_pipeline(&self.wireframe_render_pipeline);
+            render_pass.set_immediates(0, bytemuck::bytes_of(&VoxelImmediates::new(matrix, self.sun_position)));
+
+            for (&key, subchunk) in &self.subchunks {
+                if Self::is_subchunk_visible(frustum, key) {
+                    let chunk_origin = glam::IVec3::new(key.0.x, 0, key.0.y) * SUBCHUNK_SIZE_I32;
+                    let chunk_offset = chunk_origin.as_vec3() - camera_pos;
+
+                    render_pass.set_immediates(VoxelImmediates::CHUNK_OFFSET, bytemuck::bytes_of(&chunk_offset.to_array()));
+
+                    for buffer in [&subchunk.solid, &subchunk.translucent.buffer] {
+                        if buffer.count > 0 {
+                            render_pass.set_vertex_buffer(0, buffer.vertices.slice(..));
+                            render_pass.set_index_buffer(buffer.indices.slice(..), wgpu::IndexFormat::Uint32);
+                            render_pass.draw_indexed(0..buffer.count as u32, 0, 0..1);
+
+                            render_info.draw_calls += 1;
+                        }
+                    }
+                }
+            }
+        }
+
         if self.cloud_indices_count > 0 {
             render_pass.set_pipeline(&self.cloud_render_pipeline);
             render_pass.set_immediates(
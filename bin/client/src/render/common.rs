@@ -1,4 +1,4 @@
-use std::collections::hash_map::Entry;
+use std::{collections::hash_map::Entry, ops::Range};
 
 use ahash::{HashMap, HashMapExt};
 use etagere::{AllocId, AtlasAllocator};
@@ -34,6 +34,10 @@ impl CommonVertex {
     };
 }
 
+/// Handle to an RGBA image stored in the renderer's atlas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageId(AllocId);
+
 #[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
 pub struct ShapeData {
@@ -115,12 +119,271 @@ pub enum ObjectFit {
     Cover,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+struct DrawBatch {
+    clip: Option<Rect>,
+    layer: u8,
+    indices: Range<u32>,
+}
+
+/// Ranges of the index buffer sharing a clip region and a layer, each one is
+/// a single draw call.
+#[derive(Debug, Default)]
+struct DrawBatches {
+    batches: Vec<DrawBatch>,
+    /// First index not covered by any batch yet.
+    start: u32,
+}
+
+impl DrawBatches {
+    /// Closes the batch of indices pushed since the previous flush, up to
+    /// `end`.
+    fn flush(&mut self, end: u32, clip: Option<Rect>, layer: u8) {
+        if end > self.start {
+            self.batches.push(DrawBatch {
+                clip,
+                layer,
+                indices: self.start..end,
+            });
+        }
+
+        self.start = end;
+    }
+
+    /// Reorders `indices` so that batches are drawn lowest layer first,
+    /// merging neighbouring batches that end up sharing a layer and a clip.
+    fn sort_by_layer(&mut self, indices: &mut Vec<u32>) {
+        if self.batches.iter().all(|batch| batch.layer == 0) {
+            return;
+        }
+
+        self.batches.sort_by_key(|batch| batch.layer);
+
+        let mut sorted = Vec::with_capacity(indices.len());
+        let mut batches: Vec<DrawBatch> = Vec::with_capacity(self.batches.len());
+
+        for batch in self.batches.drain(..) {
+            let start = sorted.len() as u32;
+
+            sorted.extend_from_slice(&indices[batch.indices.start as usize..batch.indices.end as usize]);
+
+            let end = sorted.len() as u32;
+
+            match batches.last_mut() {
+                Some(last) if last.layer == batch.layer && last.clip == batch.clip => last.indices.end = end,
+                _ => batches.push(DrawBatch {
+                    clip: batch.clip,
+                    layer: batch.layer,
+                    indices: start..end,
+                }),
+            }
+        }
+
+        *indices = sorted;
+        self.batches = batches;
+    }
+}
+
+/// Appends a rounded rectangle tessellated with `segments` per corner, so
+/// any number of them share the current draw batch.
+fn push_round_rect_geometry(buffers: &mut RawRenderBuffer<CommonVertex>, rect: RRect, segments: u32, color: Color) {
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    let base = buffers.vertices.len() as u32;
+
+    rect.tessellate(segments, &mut positions, &mut indices);
+
+    buffers.indices.extend(indices.into_iter().map(|index| base + index));
+    buffers.vertices.extend(positions.into_iter().map(|position| CommonVertex {
+        position,
+        local_uv: glam::Vec2::ZERO,
+        half_size: [0.0; 2],
+        radii: Thickness::default(),
+        color: color.as_value(),
+        mode: 2,
+    }));
+}
+
+/// Applies `matrix` to already pushed vertices. [`CommonRenderer::transformed`]
+/// calls it once per nesting level, innermost first, so the matrices compose
+/// as `outer * inner`.
+fn transform_vertices(vertices: &mut [CommonVertex], matrix: glam::Mat4) {
+    for vertex in vertices {
+        vertex.position = matrix.transform_point3(vertex.position.extend(0.0)).truncate();
+    }
+}
+
+/// Dash pattern for [`CommonRenderer::draw_lines_styled`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub struct LineStyle {
+    pub dash_len: f32,
+    pub gap_len: f32,
+}
+
+#[allow(dead_code)]
+impl LineStyle {
+    pub const DASHED: Self = Self { dash_len: 8.0, gap_len: 6.0 };
+    pub const DOTTED: Self = Self { dash_len: 2.0, gap_len: 4.0 };
+
+    /// Splits the line from `from` to `to` into its visible dashes. The
+    /// pattern restarts at `from`, and the last dash is cut at `to`.
+    fn dashes(self, from: glam::Vec2, to: glam::Vec2) -> Vec<(glam::Vec2, glam::Vec2)> {
+        let length = from.distance(to);
+
+        if length <= 0.0 || self.dash_len <= 0.0 {
+            return Vec::new();
+        }
+
+        if self.gap_len <= 0.0 {
+            return vec![(from, to)];
+        }
+
+        let direction = (to - from) / length;
+        let period = self.dash_len + self.gap_len;
+
+        (0..(length / period).ceil() as usize)
+            .map(|i| {
+                let start = i as f32 * period;
+                let end = (start + self.dash_len).min(length);
+
+                (from + direction * start, from + direction * end)
+            })
+            .collect()
+    }
+}
+
+/// How glyphs are rasterized into the atlas.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(dead_code)]
+pub enum TextMode {
+    /// Every glyph is rasterized at the exact size it is drawn at.
+    #[default]
+    Bitmap,
+    /// Every glyph is baked once as a signed distance field and scaled to the
+    /// requested size, which keeps edges sharp at any size.
+    Sdf,
+}
+
+/// Where a line may be broken relative to a shaped cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BreakOpportunity {
+    NoBreak,
+    /// After the cluster, e.g. after a hyphen, a slash or a zero-width space.
+    After,
+    /// After the cluster, which doesn't count towards the width of the line it
+    /// ends.
+    Space,
+    /// After the cluster, drawing a hyphen at the end of the line if taken.
+    SoftHyphen,
+    /// Both before and after the cluster, scripts like CJK don't separate
+    /// words with spaces.
+    Ideographic,
+    /// The cluster is a line break.
+    Mandatory,
+}
+
+impl BreakOpportunity {
+    fn of(source: &str, whitespace: Whitespace) -> Self {
+        match source.chars().next() {
+            _ if matches!(whitespace, Whitespace::Newline) => Self::Mandatory,
+            Some('\u{AD}') => Self::SoftHyphen,
+            Some('-' | '/' | '\u{2010}' | '\u{200B}') => Self::After,
+            Some(character) if Self::is_ideographic(character) => Self::Ideographic,
+            _ if matches!(whitespace, Whitespace::Space | Whitespace::Tab) => Self::Space,
+            _ => Self::NoBreak,
+        }
+    }
+
+    const fn is_ideographic(character: char) -> bool {
+        matches!(character, '\u{2E80}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' | '\u{F900}'..='\u{FAFF}' | '\u{FF00}'..='\u{FFEF}')
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ShapedCluster {
+    advance: f32,
+    glyphs: Vec<(u16, f32, f32)>,
+    opportunity: BreakOpportunity,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct WrappedLine {
+    clusters: Range<usize>,
+    width: f32,
+    hyphen: bool,
+}
+
+/// Greedily fills lines of at most `max_width` with `clusters`, breaking at
+/// the last opportunity that fits or between any two clusters if there is
+/// none.
+fn wrap_lines(clusters: &[ShapedCluster], max_width: Option<f32>, hyphen_width: f32) -> Vec<WrappedLine> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut width = 0.0;
+    let mut last_break: Option<(usize, f32, bool)> = None;
+
+    for (index, cluster) in clusters.iter().enumerate() {
+        if cluster.opportunity == BreakOpportunity::Mandatory {
+            lines.push(WrappedLine {
+                clusters: start..index,
+                width,
+                hyphen: false,
+            });
+
+            start = index + 1;
+            width = 0.0;
+            last_break = None;
+
+            continue;
+        }
+
+        if cluster.opportunity == BreakOpportunity::Ideographic && index > start {
+            last_break = Some((index, width, false));
+        }
+
+        if let Some(max_width) = max_width
+            && cluster.opportunity != BreakOpportunity::Space
+            && index > start
+            && width + cluster.advance > max_width
+        {
+            let (end, line_width, hyphen) = last_break.take().unwrap_or((index, width, false));
+
+            lines.push(WrappedLine {
+                clusters: start..end,
+                width: line_width,
+                hyphen,
+            });
+
+            start = end;
+            width = clusters[start..index].iter().map(|cluster| cluster.advance).sum();
+        }
+
+        width += cluster.advance;
+
+        match cluster.opportunity {
+            BreakOpportunity::After | BreakOpportunity::Ideographic => last_break = Some((index + 1, width, false)),
+            BreakOpportunity::Space => last_break = Some((index + 1, width - cluster.advance, false)),
+            BreakOpportunity::SoftHyphen => last_break = Some((index + 1, width + hyphen_width, true)),
+            BreakOpportunity::NoBreak | BreakOpportunity::Mandatory => (),
+        }
+    }
+
+    lines.push(WrappedLine {
+        clusters: start..clusters.len(),
+        width,
+        hyphen: false,
+    });
+
+    lines
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct GlyphKey(CacheKey, u32, u16);
+pub struct GlyphKey(CacheKey, u32, u16, TextMode);
 
 impl GlyphKey {
-    const fn new(font: CacheKey, font_size: f32, glyph: u16) -> Self {
-        Self(font, font_size.to_bits(), glyph)
+    const fn new(font: CacheKey, font_size: f32, glyph: u16, mode: TextMode) -> Self {
+        Self(font, font_size.to_bits(), glyph, mode)
     }
 }
 
@@ -138,6 +401,7 @@ pub struct CommonRenderer {
     font_name_map: HashMap<String, usize>,
     glyph_map: HashMap<GlyphKey, (AllocId, glam::IVec2)>,
     fonts: Vec<OwnedFont>,
+    text_mode: TextMode,
 
     // COMMON RENDERING
     pub(crate) buffers: RawRenderBuffer<CommonVertex>,
@@ -145,10 +409,19 @@ pub struct CommonRenderer {
     // VERTICES TRANSFORMATION
     transform: Option<glam::Mat4>,
 
+    // ROUNDED RECTANGLES AND ELLIPSES
+    round_rect_segments: Option<u32>,
+    ellipse_segments: u32,
+
     matrix: Option<glam::Mat4>,
     window_matrix: glam::Mat4,
+    /// Area of the window covered by [`Self::window_matrix`].
+    viewport: Rect,
 
-    pub clip: Option<(glam::Vec2, glam::Vec2)>,
+    // CLIPPING
+    clip_stack: Vec<Rect>,
+    layer: u8,
+    batches: DrawBatches,
 }
 
 pub struct OwnedFont {
@@ -157,9 +430,43 @@ pub struct OwnedFont {
     pub key: CacheKey,
 }
 
+/// Vertical metrics of a font scaled to a particular size, in pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    /// Distance from the baseline to the top of the tallest glyphs.
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of the lowest glyphs.
+    pub descent: f32,
+    /// Recommended extra space between two lines.
+    pub line_gap: f32,
+}
+
+impl FontMetrics {
+    pub fn new(font_ref: FontRef, size: f32) -> Self {
+        let metrics = font_ref.metrics(&[]).scale(size);
+
+        Self {
+            ascent: metrics.ascent,
+            descent: metrics.descent.abs(),
+            line_gap: metrics.leading,
+        }
+    }
+
+    /// Distance between the baselines of two consecutive lines.
+    pub const fn line_height(&self) -> f32 {
+        self.ascent + self.descent + self.line_gap
+    }
+}
+
 impl CommonRenderer {
+    const DEFAULT_ELLIPSE_SEGMENTS: u32 = 48;
     const PREALLOCATE_INDICES: usize = Self::PREALLOCATE_VERTICES * 2;
     const PREALLOCATE_VERTICES: usize = 16 * 16 * 16 * 72;
+    /// Size SDF glyphs are baked at.
+    const SDF_BAKE_SIZE: f32 = 48.0;
+    /// Distance in pixels of the bake size covered by the distance field on
+    /// each side of a glyph edge.
+    const SDF_SPREAD: u32 = 6;
 
     #[allow(clippy::too_many_lines)]
     pub fn new(context: &WindowContext) -> Self {
@@ -216,6 +523,18 @@ impl CommonRenderer {
             ..Default::default()
         });
 
+        // SDF glyphs are almost always drawn smaller than they were baked at, so
+        // they need linear minification to not alias.
+        let sdf_sampler = context.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::MipmapFilterMode::Nearest,
+            ..Default::default()
+        });
+
         let texture_bind_group_layout = context.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -244,6 +563,12 @@ impl CommonRenderer {
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
             ],
             label: Some("Common Renderer Bind Group Layout"),
         });
@@ -263,6 +588,10 @@ impl CommonRenderer {
                     binding: 2,
                     resource: wgpu::BindingResource::Sampler(&sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&sdf_sampler),
+                },
             ],
             label: Some("Common Renderer Bind Group"),
         });
@@ -338,13 +667,20 @@ impl CommonRenderer {
             font_name_map: HashMap::new(),
             glyph_map: HashMap::new(),
             fonts: Vec::new(),
+            text_mode: TextMode::default(),
 
             buffers: RawRenderBuffer::new(),
 
             transform: None,
+            round_rect_segments: None,
+            ellipse_segments: Self::DEFAULT_ELLIPSE_SEGMENTS,
             window_matrix: glam::Mat4::IDENTITY,
+            viewport: Rect::new(glam::Vec2::ZERO, context.window_size().as_vec2()),
             matrix: None,
-            clip: None,
+
+            clip_stack: Vec::new(),
+            layer: 0,
+            batches: DrawBatches::default(),
         }
     }
 
@@ -369,14 +705,257 @@ impl CommonRenderer {
     }
 
     pub fn set_window_matrix(&mut self, queue: &wgpu::Queue, matrix: glam::Mat4) {
+        let inverse = matrix.inverse();
+        let min = inverse.project_point3(glam::Vec3::new(-1.0, -1.0, 0.0)).truncate();
+        let max = inverse.project_point3(glam::Vec3::new(1.0, 1.0, 0.0)).truncate();
+
         self.window_matrix = matrix;
+        self.viewport = Rect::new(min.min(max), (max - min).abs());
 
         queue.write_buffer(&self.matrix_buffer, 0, bytemuck::cast_slice(&matrix.to_cols_array()));
     }
 
+    /// Effective transform applied to shapes drawn inside
+    /// [`Self::transformed`], [`None`] outside of it.
+    #[allow(dead_code)]
+    pub const fn transform(&self) -> Option<glam::Mat4> {
+        self.transform
+    }
+
+    /// Draws everything pushed by `f` with `matrix` applied on top of the
+    /// current transform, restoring the previous one afterward. Nested calls
+    /// compose, so a rotation inside a scale gets both.
+    pub fn transformed<R>(&mut self, matrix: glam::Mat4, f: impl FnOnce(&mut Self) -> R) -> R {
+        let previous = self.transform;
+        let start = self.buffers.vertices.len();
+
+        self.transform = Some(previous.map_or(matrix, |transform| transform * matrix));
+
+        let result = f(self);
+
+        // Outer calls transform these vertices again once their closure
+        // returns, which is what composes the matrices.
+        transform_vertices(&mut self.buffers.vertices[start..], matrix);
+
+        self.transform = previous;
+
+        result
+    }
+
+    /// Selects how rounded rectangles are drawn: `None` renders them as
+    /// anti-aliased SDF quads, while `Some(segments)` tessellates every corner
+    /// into the given number of segments, trading smoothness for cheaper
+    /// fragment work.
+    pub const fn set_round_rect_segments(&mut self, segments: Option<u32>) {
+        self.round_rect_segments = segments;
+    }
+
+    /// Selects how text drawn from now on is rasterized, see [`TextMode`].
+    #[allow(dead_code)]
+    pub const fn set_text_mode(&mut self, mode: TextMode) {
+        self.text_mode = mode;
+    }
+
+    /// Sets the number of segments used to tessellate ellipses and circle
+    /// outlines.
     #[allow(dead_code)]
-    pub const fn set_transform(&mut self, transform: Option<glam::Mat4>) {
-        self.transform = transform;
+    pub fn set_ellipse_segments(&mut self, segments: u32) {
+        self.ellipse_segments = segments.max(3);
+    }
+
+    /// Restricts everything drawn until the matching [`Self::pop_clip`] to
+    /// `rect`, intersected with the currently active clip region. Inside
+    /// [`Self::transformed`] the clip covers the screen bounds of the
+    /// transformed `rect`.
+    pub fn push_clip(&mut self, rect: Rect) {
+        self.flush_batch();
+
+        let (min, max) = self.screen_bounds(rect);
+        let rect = Rect::new(min, max - min);
+        let rect = self.clip_stack.last().map_or(rect, |current| current.intersection(&rect));
+
+        self.clip_stack.push(rect);
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.flush_batch();
+        self.clip_stack.pop();
+    }
+
+    /// Draws everything pushed by `f` on `layer`, restoring the previous layer
+    /// afterward. Layers are drawn from lowest to highest, and in call order
+    /// within a layer, so overlays like tooltips can be drawn early and still
+    /// end up on top. Everything is drawn on layer `0` by default.
+    #[allow(dead_code)]
+    pub fn with_layer<R>(&mut self, layer: u8, f: impl FnOnce(&mut Self) -> R) -> R {
+        let previous = self.layer;
+
+        self.flush_batch();
+        self.layer = layer;
+
+        let result = f(self);
+
+        self.flush_batch();
+        self.layer = previous;
+
+        result
+    }
+
+    /// Whether anything drawn inside `rect` could end up on screen, that is
+    /// whether it overlaps both the window and the active clip region. Lets
+    /// callers skip building shapes that would be thrown away anyway.
+    pub fn is_visible(&self, rect: Rect) -> bool {
+        let (min, max) = self.screen_bounds(rect);
+        let bounds = self.clip_stack.last().map_or(self.viewport, |clip| clip.intersection(&self.viewport));
+
+        min.cmplt(bounds.origin + bounds.size).all() && max.cmpgt(bounds.origin).all()
+    }
+
+    /// Corners of the screen-space box around `rect` once the current
+    /// transform is applied.
+    fn screen_bounds(&self, rect: Rect) -> (glam::Vec2, glam::Vec2) {
+        self.transform.map_or((rect.origin, rect.origin + rect.size), |transform| {
+            let corners = [
+                rect.origin,
+                rect.origin.with_x(rect.origin.x + rect.size.x),
+                rect.origin.with_y(rect.origin.y + rect.size.y),
+                rect.origin + rect.size,
+            ]
+            .map(|corner| transform.transform_point3(corner.extend(0.0)).truncate());
+
+            corners
+                .into_iter()
+                .fold((corners[0], corners[0]), |(min, max), corner| (min.min(corner), max.max(corner)))
+        })
+    }
+
+    fn flush_batch(&mut self) {
+        self.batches
+            .flush(self.buffers.indices.len() as u32, self.clip_stack.last().copied(), self.layer);
+    }
+
+    fn write_to_atlas(&self, queue: &wgpu::Queue, origin: etagere::Point, image: &image::RgbaImage) {
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfoBase {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: origin.x.cast_unsigned(),
+                    y: origin.y.cast_unsigned(),
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            image.as_raw(),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * image.width()),
+                rows_per_image: Some(image.height()),
+            },
+            wgpu::Extent3d {
+                width: image.width(),
+                height: image.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Allocates space for `image` in the atlas and uploads it. Returns
+    /// [`None`] if the atlas is full.
+    pub fn upload_image(&mut self, queue: &wgpu::Queue, image: &image::RgbaImage) -> Option<ImageId> {
+        let alloc = self.atlas.allocate(etagere::size2(image.width().cast_signed(), image.height().cast_signed()))?;
+
+        self.write_to_atlas(queue, alloc.rectangle.min, image);
+
+        Some(ImageId(alloc.id))
+    }
+
+    /// Overwrites a previously uploaded image. `image` must not be larger
+    /// than the one it was allocated for.
+    pub fn update_image(&self, queue: &wgpu::Queue, id: ImageId, image: &image::RgbaImage) {
+        self.write_to_atlas(queue, self.atlas.get(id.0).min, image);
+    }
+
+    pub fn remove_image(&mut self, id: ImageId) {
+        self.atlas.deallocate(id.0);
+    }
+
+    /// Draws an uploaded image stretched over the given rectangle, multiplying
+    /// its texels by `tint`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn draw_image(&mut self, id: ImageId, origin: glam::Vec2, size: glam::Vec2, tint: Color) {
+        let rect = self.atlas.get(id.0);
+        let atlas_size = self.atlas.size();
+        let atlas_size = glam::Vec2::new(atlas_size.width as f32, atlas_size.height as f32);
+        let uv_min = glam::Vec2::new(rect.min.x as f32, rect.min.y as f32) / atlas_size;
+        let uv_max = glam::Vec2::new(rect.max.x as f32, rect.max.y as f32) / atlas_size;
+
+        self.push_image_quad(origin, size, uv_min, uv_max, tint);
+    }
+
+    /// Draws an uploaded image over `rect` as a nine-slice: the corners keep
+    /// their size, the edges stretch along one axis and the center along both.
+    /// `insets` are given in image pixels. Corners are scaled down when `rect`
+    /// is too small to fit them.
+    #[allow(dead_code, clippy::cast_precision_loss)]
+    pub fn draw_nine_slice(&mut self, rect: Rect, id: ImageId, insets: Thickness, tint: Color) {
+        let source = self.atlas.get(id.0);
+        let atlas_size = self.atlas.size();
+        let atlas_size = glam::Vec2::new(atlas_size.width as f32, atlas_size.height as f32);
+        let source_min = glam::Vec2::new(source.min.x as f32, source.min.y as f32);
+        let source_max = glam::Vec2::new(source.max.x as f32, source.max.y as f32);
+
+        let start = glam::Vec2::new(insets.left(), insets.top()).max(glam::Vec2::ZERO);
+        let end = glam::Vec2::new(insets.right(), insets.bottom()).max(glam::Vec2::ZERO);
+        let total = start + end;
+        let scale = glam::Vec2::select(total.cmpgt(rect.size), rect.size.max(glam::Vec2::ZERO) / total, glam::Vec2::ONE);
+
+        let positions = [
+            rect.origin,
+            rect.origin + start * scale,
+            rect.origin + rect.size - end * scale,
+            rect.origin + rect.size,
+        ];
+        let uvs = [source_min, source_min + start, source_max - end, source_max].map(|uv| uv / atlas_size);
+
+        for row in 0..3 {
+            for column in 0..3 {
+                let min = glam::Vec2::new(positions[column].x, positions[row].y);
+                let max = glam::Vec2::new(positions[column + 1].x, positions[row + 1].y);
+
+                if max.cmpgt(min).all() {
+                    let uv_min = glam::Vec2::new(uvs[column].x, uvs[row].y);
+                    let uv_max = glam::Vec2::new(uvs[column + 1].x, uvs[row + 1].y);
+
+                    self.push_image_quad(min, max - min, uv_min, uv_max, tint);
+                }
+            }
+        }
+    }
+
+    fn push_image_quad(&mut self, origin: glam::Vec2, size: glam::Vec2, uv_min: glam::Vec2, uv_max: glam::Vec2, tint: Color) {
+        let base = self.buffers.vertices.len() as u32;
+
+        self.buffers.vertices.extend(
+            [
+                origin,
+                origin + glam::Vec2::new(size.x, 0.0),
+                origin + size,
+                origin + glam::Vec2::new(0.0, size.y),
+            ]
+            .into_iter()
+            .zip([uv_min, glam::Vec2::new(uv_max.x, uv_min.y), uv_max, glam::Vec2::new(uv_min.x, uv_max.y)])
+            .map(|(position, local_uv)| CommonVertex {
+                position,
+                local_uv,
+                color: tint.as_value(),
+                half_size: [0.0; 2],
+                radii: Thickness::default(),
+                mode: 3,
+            }),
+        );
+
+        self.buffers.indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
     }
 
     /// # Errors
@@ -399,40 +978,73 @@ impl CommonRenderer {
         // }
     }
 
-    pub fn measure<F: AsRef<str>, T: AsRef<str>>(&self, font: F, text: T, size: f32, _max_width: Option<f32>) -> Option<glam::Vec2> {
+    pub fn measure<F: AsRef<str>, T: AsRef<str>>(&self, font: F, text: T, size: f32, max_width: Option<f32>) -> Option<glam::Vec2> {
         self.font_name_map.get(font.as_ref()).copied().map(|font_index| {
-            let text = text.as_ref();
-
-            let font_ref = FontRef::from_index(&self.fonts[font_index].data, 0).unwrap();
-
-            let mut shape_context = ShapeContext::new();
-            let mut shaper = shape_context.builder(font_ref).size(size).build();
-            let _metrics = font_ref.glyph_metrics(&[]).scale(size);
-
-            shaper.add_str(text);
-
-            let mut metrics = glam::Vec2::ZERO;
-            let mut x = 0.0;
-            let mut y = size;
-
-            shaper.shape_with(|cluster| {
-                use swash::text::cluster::Whitespace;
+            let OwnedFont { data, offset, key, .. } = &self.fonts[font_index];
+            let font_ref = FontRef {
+                data,
+                offset: *offset,
+                key: *key,
+            };
+
+            let (_, lines) = Self::layout_text(font_ref, text.as_ref(), size, max_width);
+            let metrics = FontMetrics::new(font_ref, size);
+            let height = metrics
+                .line_height()
+                .mul_add(lines.len().saturating_sub(1) as f32, metrics.ascent + metrics.descent);
+
+            glam::Vec2::new(lines.iter().map(|line| line.width).fold(0.0, f32::max), height)
+        })
+    }
 
-                if matches!(cluster.info.whitespace(), Whitespace::Newline) {
-                    metrics.x = metrics.x.max(x);
+    /// Returns the vertical metrics of `font` at `size`, or `None` if no such
+    /// font was added.
+    pub fn font_metrics<F: AsRef<str>>(&self, font: F, size: f32) -> Option<FontMetrics> {
+        self.font_name_map.get(font.as_ref()).copied().map(|font_index| {
+            let OwnedFont { data, offset, key, .. } = &self.fonts[font_index];
+            let font_ref = FontRef {
+                data,
+                offset: *offset,
+                key: *key,
+            };
 
-                    x = 0.0;
-                    y += size;
-                }
+            FontMetrics::new(font_ref, size)
+        })
+    }
 
-                for _glyph in cluster.glyphs {
-                    x += cluster.advance();
-                }
+    /// Shapes `text` and splits it into lines no wider than `max_width`.
+    ///
+    /// Lines break at spaces, hyphens, slashes, around ideographs and at soft
+    /// hyphens and zero-width spaces. A word that doesn't fit on a line by
+    /// itself is broken between characters instead of overflowing.
+    fn layout_text(font_ref: FontRef, text: &str, size: f32, max_width: Option<f32>) -> (Vec<ShapedCluster>, Vec<WrappedLine>) {
+        let mut shape_context = ShapeContext::new();
+        let mut shaper = shape_context.builder(font_ref).size(size).build();
+
+        shaper.add_str(text);
+
+        let mut clusters = Vec::new();
+
+        shaper.shape_with(|cluster| {
+            let source = text.get(cluster.source.start as usize..cluster.source.end as usize).unwrap_or_default();
+            let opportunity = BreakOpportunity::of(source, cluster.info.whitespace());
+            let invisible = matches!(opportunity, BreakOpportunity::Mandatory | BreakOpportunity::SoftHyphen) || source.starts_with('\u{200B}');
+
+            clusters.push(ShapedCluster {
+                advance: if invisible { 0.0 } else { cluster.advance() },
+                glyphs: if invisible || cluster.info.is_whitespace() {
+                    Vec::new()
+                } else {
+                    cluster.glyphs.iter().map(|glyph| (glyph.id, glyph.x, glyph.y)).collect()
+                },
+                opportunity,
             });
+        });
 
-            metrics.x = metrics.x.max(x);
-            metrics.with_y(y)
-        })
+        let hyphen_width = font_ref.glyph_metrics(&[]).scale(size).advance_width(font_ref.charmap().map('-'));
+        let lines = wrap_lines(&clusters, max_width, hyphen_width);
+
+        (clusters, lines)
     }
 
     fn push_quad(&mut self, positions: [glam::Vec2; 4], local_uvs: [glam::Vec2; 4], half_size: glam::Vec2, radii: Thickness, color: Color) {
@@ -451,15 +1063,145 @@ impl CommonRenderer {
     }
 
     #[allow(dead_code)]
-    pub fn draw_circle(&mut self, origin: glam::Vec2, radius: f32, color: Color) {
-        self.draw_round_rect(origin, glam::Vec2::splat(radius * 2.0), Thickness::all(radius), color);
+    pub fn draw_circle(&mut self, center: glam::Vec2, radius: f32, color: Color) {
+        if radius > 0.0 {
+            self.draw_round_rect(center - radius, glam::Vec2::splat(radius * 2.0), Thickness::all(radius), color);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn draw_circle_outline(&mut self, center: glam::Vec2, radius: f32, thickness: f32, color: Color) {
+        if radius > 0.0 && thickness > 0.0 {
+            self.push_ellipse(center, glam::Vec2::splat(radius), Some(thickness), color);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn draw_ellipse(&mut self, center: glam::Vec2, radii: glam::Vec2, color: Color) {
+        if radii.cmpgt(glam::Vec2::ZERO).all() {
+            self.push_ellipse(center, radii, None, color);
+        }
+    }
+
+    /// Tessellates an ellipse, or a ring of given thickness along its edge.
+    #[allow(clippy::cast_precision_loss)]
+    fn push_ellipse(&mut self, center: glam::Vec2, radii: glam::Vec2, thickness: Option<f32>, color: Color) {
+        let segments = self.ellipse_segments;
+        let base = self.buffers.vertices.len() as u32;
+        let vertex = |position| CommonVertex {
+            position,
+            local_uv: glam::Vec2::ZERO,
+            half_size: [0.0; 2],
+            radii: Thickness::default(),
+            color: color.as_value(),
+            mode: 2,
+        };
+
+        if let Some(thickness) = thickness {
+            let inner_radii = (radii - thickness).max(glam::Vec2::ZERO);
+
+            for i in 0..segments {
+                let direction = glam::Vec2::from_angle(std::f32::consts::TAU * i as f32 / segments as f32);
+
+                self.buffers.vertices.push(vertex(center + direction * radii));
+                self.buffers.vertices.push(vertex(center + direction * inner_radii));
+            }
+
+            for i in 0..segments {
+                let [outer, inner] = [base + i * 2, base + i * 2 + 1];
+                let [next_outer, next_inner] = [base + (i + 1) % segments * 2, base + (i + 1) % segments * 2 + 1];
+
+                self.buffers.indices.extend([outer, next_outer, next_inner, outer, next_inner, inner]);
+            }
+        } else {
+            self.buffers.vertices.push(vertex(center));
+
+            for i in 0..segments {
+                let direction = glam::Vec2::from_angle(std::f32::consts::TAU * i as f32 / segments as f32);
+
+                self.buffers.vertices.push(vertex(center + direction * radii));
+            }
+
+            for i in 0..segments {
+                self.buffers.indices.extend([base, base + 1 + i, base + 1 + (i + 1) % segments]);
+            }
+        }
+    }
+
+    /// Fills a convex polygon by fanning triangles out from its first point.
+    pub fn draw_polygon(&mut self, points: &[glam::Vec2], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let base = self.buffers.vertices.len() as u32;
+
+        self.buffers.vertices.extend(points.iter().map(|&position| CommonVertex {
+            position,
+            local_uv: glam::Vec2::ZERO,
+            half_size: [0.0; 2],
+            radii: Thickness::default(),
+            color: color.as_value(),
+            mode: 2,
+        }));
+
+        for i in 1..points.len() as u32 - 1 {
+            self.buffers.indices.extend([base, base + i, base + i + 1]);
+        }
+    }
+
+    pub fn draw_line(&mut self, from: glam::Vec2, to: glam::Vec2, thickness: f32, color: Color) {
+        let offset = (to - from).perp().normalize_or_zero() * (thickness / 2.0);
+
+        if offset != glam::Vec2::ZERO {
+            self.draw_polygon(&[from - offset, to - offset, to + offset, from + offset], color);
+        }
+    }
+
+    /// Same as [`Self::draw_line`] for every `(from, to)` pair, but only draws
+    /// the dashes of `style`.
+    #[allow(dead_code)]
+    pub fn draw_lines_styled(&mut self, lines: &[(glam::Vec2, glam::Vec2)], thickness: f32, style: LineStyle, color: Color) {
+        for &(from, to) in lines {
+            for (start, end) in style.dashes(from, to) {
+                self.draw_line(start, end, thickness, color);
+            }
+        }
     }
 
     pub fn draw_rect(&mut self, origin: glam::Vec2, size: glam::Vec2, color: Color) {
         self.draw_round_rect(origin, size, Thickness::default(), color);
     }
 
+    /// Strokes the inside of a rectangle's edge with four thin rectangles.
+    pub fn draw_rect_outline(&mut self, origin: glam::Vec2, size: glam::Vec2, thickness: f32, color: Color) {
+        let thickness = thickness.min(size.x / 2.0).min(size.y / 2.0);
+
+        if thickness <= 0.0 {
+            return;
+        }
+
+        let inner_height = size.y - thickness * 2.0;
+
+        self.draw_rect(origin, glam::Vec2::new(size.x, thickness), color);
+        self.draw_rect(origin + glam::Vec2::new(0.0, size.y - thickness), glam::Vec2::new(size.x, thickness), color);
+        self.draw_rect(origin + glam::Vec2::new(0.0, thickness), glam::Vec2::new(thickness, inner_height), color);
+        self.draw_rect(
+            origin + glam::Vec2::new(size.x - thickness, thickness),
+            glam::Vec2::new(thickness, inner_height),
+            color,
+        );
+    }
+
     pub fn draw_round_rect(&mut self, origin: glam::Vec2, size: glam::Vec2, radii: Thickness, color: Color) {
+        if let Some(segments) = self.round_rect_segments
+            && radii.any_above(0.0)
+        {
+            push_round_rect_geometry(&mut self.buffers, RRect::new(origin, size, radii), segments, color);
+
+            return;
+        }
+
         let h = size * 0.5;
         let c = origin + h;
 
@@ -507,7 +1249,7 @@ impl CommonRenderer {
         text: T,
         color: Color,
         font_size: f32,
-        _max_width: Option<f32>,
+        max_width: Option<f32>,
     ) {
         if let Some(font_index) = self.font_name_map.get(font.as_ref()).copied() {
             let text = text.as_ref();
@@ -516,121 +1258,154 @@ impl CommonRenderer {
             let key = *key;
             let font_ref = FontRef { data, offset: *offset, key };
 
-            let mut shape_context = ShapeContext::new();
+            let mode = self.text_mode;
+            let (bake_size, padding, mode_id) = match mode {
+                TextMode::Bitmap => (font_size, 0, 1),
+                TextMode::Sdf => (Self::SDF_BAKE_SIZE, Self::SDF_SPREAD.cast_signed(), 4),
+            };
+
+            let scale = font_size / bake_size;
+
             let mut scale_context = ScaleContext::new();
-            let mut scaler = scale_context.builder(font_ref).hint(true).size(font_size).build();
-            let mut shaper = shape_context.builder(font_ref).size(font_size).build();
+            let mut scaler = scale_context.builder(font_ref).hint(mode == TextMode::Bitmap).size(bake_size).build();
 
-            shaper.add_str(text);
+            let (clusters, lines) = Self::layout_text(font_ref, text, font_size, max_width);
+            let hyphen = font_ref.charmap().map('-');
 
-            let mut x = origin.x;
-            let mut y = origin.y + font_size;
+            let mut push_glyph = |id: u16, x: f32, y: f32, glyph_x: f32, glyph_y: f32| {
+                let key = GlyphKey::new(key, bake_size, id, mode);
+                let (rect, offset) = match self.glyph_map.entry(key) {
+                    Entry::Occupied(entry) => {
+                        let (alloc, offset) = *entry.get();
 
-            shaper.shape_with(|cluster| {
-                if matches!(cluster.info.whitespace(), Whitespace::Newline) {
-                    x = origin.x;
-                    y += font_size;
-                }
+                        (self.atlas.get(alloc), offset)
+                    }
+                    Entry::Vacant(entry) => {
+                        // SDF glyphs are shared between every size, so they are baked without the
+                        // subpixel offset of this particular run.
+                        let glyph_offset = match mode {
+                            TextMode::Bitmap => Vector::new(glyph_x, glyph_y),
+                            TextMode::Sdf => Vector::new(0.0, 0.0),
+                        };
 
-                for glyph in cluster.glyphs {
-                    if !cluster.info.is_whitespace() {
-                        let key = GlyphKey::new(key, font_size, glyph.id);
-                        let (rect, offset) = match self.glyph_map.entry(key) {
-                            Entry::Occupied(entry) => {
-                                let (alloc, offset) = *entry.get();
-
-                                (self.atlas.get(alloc), offset)
-                            }
-                            Entry::Vacant(entry) => {
-                                let image = Render::new(&[Source::ColorOutline(0), Source::ColorBitmap(StrikeWith::BestFit), Source::Outline])
-                                    .format(Format::Alpha)
-                                    .offset(Vector::new(glyph.x, glyph.y))
-                                    .render(&mut scaler, glyph.id)
-                                    .unwrap();
-
-                                let alloc = self
-                                    .atlas
-                                    .allocate(etagere::size2(image.placement.width.cast_signed(), image.placement.height.cast_signed()))
-                                    .unwrap();
-
-                                let buffer = image::GrayImage::from_raw(image.placement.width, image.placement.height, image.data).unwrap();
-                                let offset = glam::IVec2::new(image.placement.left, image.placement.top);
-
-                                entry.insert((alloc.id, offset));
-
-                                let buffer = image::DynamicImage::ImageLuma8(buffer);
-                                let buffer = buffer.to_rgba8().into_raw();
-
-                                queue.write_texture(
-                                    wgpu::TexelCopyTextureInfoBase {
-                                        texture: &self.texture,
-                                        mip_level: 0,
-                                        origin: wgpu::Origin3d {
-                                            x: alloc.rectangle.min.x.cast_unsigned(),
-                                            y: alloc.rectangle.min.y.cast_unsigned(),
-                                            z: 0,
-                                        },
-                                        aspect: wgpu::TextureAspect::All,
-                                    },
-                                    &buffer,
-                                    wgpu::TexelCopyBufferLayout {
-                                        offset: 0,
-                                        bytes_per_row: Some(4 * image.placement.width),
-                                        rows_per_image: Some(image.placement.height),
-                                    },
-                                    wgpu::Extent3d {
-                                        width: image.placement.width,
-                                        height: image.placement.height,
-                                        depth_or_array_layers: 1,
-                                    },
-                                );
-
-                                (alloc.rectangle, offset)
-                            }
+                        let image = Render::new(&[Source::ColorOutline(0), Source::ColorBitmap(StrikeWith::BestFit), Source::Outline])
+                            .format(Format::Alpha)
+                            .offset(glyph_offset)
+                            .render(&mut scaler, id)
+                            .unwrap();
+
+                        let buffer = image::GrayImage::from_raw(image.placement.width, image.placement.height, image.data).unwrap();
+                        let buffer = match mode {
+                            TextMode::Bitmap => image::DynamicImage::ImageLuma8(buffer).to_rgba8(),
+                            TextMode::Sdf => Self::distance_field(&buffer, Self::SDF_SPREAD),
                         };
 
-                        let atlas_size = self.atlas.size();
-                        let u0 = rect.min.x as f32 / atlas_size.width as f32;
-                        let v0 = rect.min.y as f32 / atlas_size.height as f32;
-                        let u1 = rect.max.x as f32 / atlas_size.width as f32;
-                        let v1 = rect.max.y as f32 / atlas_size.height as f32;
-
-                        let base = self.buffers.vertices.len() as u32;
-                        let base_point = glam::Vec2::new(x + offset.x as f32, y - offset.y as f32);
-
-                        self.buffers.vertices.extend(
-                            [
-                                base_point,
-                                base_point + glam::Vec2::new(rect.width() as f32, 0.0),
-                                base_point + glam::Vec2::new(rect.width() as f32, rect.height() as f32),
-                                base_point + glam::Vec2::new(0.0, rect.height() as f32),
-                            ]
-                            .into_iter()
-                            .zip([
-                                glam::Vec2::new(u0, v0),
-                                glam::Vec2::new(u1, v0),
-                                glam::Vec2::new(u1, v1),
-                                glam::Vec2::new(u0, v1),
-                            ])
-                            .map(|(position, local_uv)| CommonVertex {
-                                position,
-                                local_uv,
-                                color: color.as_value(),
-                                half_size: [0.0; 2],
-                                radii: Thickness::default(),
-                                mode: 1,
-                            }),
-                        );
-
-                        self.buffers.indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+                        let alloc = self
+                            .atlas
+                            .allocate(etagere::size2(buffer.width().cast_signed(), buffer.height().cast_signed()))
+                            .unwrap();
+
+                        let offset = glam::IVec2::new(image.placement.left - padding, image.placement.top + padding);
+
+                        entry.insert((alloc.id, offset));
+
+                        self.write_to_atlas(queue, alloc.rectangle.min, &buffer);
+
+                        (alloc.rectangle, offset)
+                    }
+                };
+
+                let atlas_size = self.atlas.size();
+                let u0 = rect.min.x as f32 / atlas_size.width as f32;
+                let v0 = rect.min.y as f32 / atlas_size.height as f32;
+                let u1 = rect.max.x as f32 / atlas_size.width as f32;
+                let v1 = rect.max.y as f32 / atlas_size.height as f32;
+
+                let base = self.buffers.vertices.len() as u32;
+                let size = glam::Vec2::new(rect.width() as f32, rect.height() as f32) * scale;
+                let base_point = match mode {
+                    TextMode::Bitmap => glam::Vec2::new(x + offset.x as f32, y - offset.y as f32),
+                    TextMode::Sdf => glam::Vec2::new(x + glyph_x, y - glyph_y) + offset.as_vec2() * glam::Vec2::new(1.0, -1.0) * scale,
+                };
+
+                self.buffers.vertices.extend(
+                    [base_point, base_point + size.with_y(0.0), base_point + size, base_point + size.with_x(0.0)]
+                        .into_iter()
+                        .zip([
+                            glam::Vec2::new(u0, v0),
+                            glam::Vec2::new(u1, v0),
+                            glam::Vec2::new(u1, v1),
+                            glam::Vec2::new(u0, v1),
+                        ])
+                        .map(|(position, local_uv)| CommonVertex {
+                            position,
+                            local_uv,
+                            color: color.as_value(),
+                            half_size: [0.0; 2],
+                            radii: Thickness::default(),
+                            mode: mode_id,
+                        }),
+                );
+
+                self.buffers.indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+            };
+
+            // The first line sits on the font's baseline rather than at the
+            // bottom of the em box, matching the baselines used by UI rows.
+            let metrics = FontMetrics::new(font_ref, font_size);
+            let mut y = origin.y + metrics.ascent;
+
+            for line in &lines {
+                let mut x = origin.x;
+
+                for cluster in &clusters[line.clusters.clone()] {
+                    for &(id, glyph_x, glyph_y) in &cluster.glyphs {
+                        push_glyph(id, x, y, glyph_x, glyph_y);
                     }
 
-                    x += cluster.advance();
+                    x += cluster.advance;
                 }
-            });
+
+                if line.hyphen {
+                    push_glyph(hyphen, x, y, 0.0, 0.0);
+                }
+
+                y += metrics.line_height();
+            }
         }
     }
 
+    /// Converts a glyph coverage mask into a signed distance field padded by
+    /// `spread` pixels on every side. The distance is stored in the alpha
+    /// channel, which unlike the color channels isn't affected by the sRGB
+    /// atlas format, with edges at `0.5`.
+    fn distance_field(mask: &image::GrayImage, spread: u32) -> image::RgbaImage {
+        let spread = spread.cast_signed();
+        let width = mask.width().cast_signed();
+        let height = mask.height().cast_signed();
+        let inside = |x: i32, y: i32| x >= 0 && y >= 0 && x < width && y < height && mask.get_pixel(x.cast_unsigned(), y.cast_unsigned()).0[0] >= 128;
+
+        image::RgbaImage::from_fn((width + spread * 2).cast_unsigned(), (height + spread * 2).cast_unsigned(), |x, y| {
+            let x = x.cast_signed() - spread;
+            let y = y.cast_signed() - spread;
+            let is_inside = inside(x, y);
+            let mut closest = (spread * spread) as f32;
+
+            for offset_y in -spread..=spread {
+                for offset_x in -spread..=spread {
+                    if inside(x + offset_x, y + offset_y) != is_inside {
+                        closest = closest.min((offset_x * offset_x + offset_y * offset_y) as f32);
+                    }
+                }
+            }
+
+            let distance = closest.sqrt() / spread as f32;
+            let distance = if is_inside { distance } else { -distance };
+
+            image::Rgba([255, 255, 255, ((0.5 + distance * 0.5).clamp(0.0, 1.0) * 255.0).round() as u8])
+        })
+    }
+
     // #[must_use = "RenderInfo itself needs to be extended into other"]
     // pub fn render_lines<S: Surface>(
     //     &mut self,
@@ -662,8 +1437,10 @@ impl CommonRenderer {
     // }
 
     pub fn render(&mut self, render_pass: &mut wgpu::RenderPass, context: &WindowContext) -> super::RenderInfo {
+        self.flush_batch();
+        self.batches.sort_by_layer(&mut self.buffers.indices);
+
         let vertices = self.buffers.vertices.len();
-        let indices = self.buffers.indices.len();
 
         {
             self.vbo.slice(..).map_async(wgpu::MapMode::Write, |_| ());
@@ -696,13 +1473,178 @@ impl CommonRenderer {
         }
 
         self.buffers.clear();
+        self.batches.start = 0;
+
+        let window_size = context.window_size();
 
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &self.bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.vbo.slice(..));
         render_pass.set_index_buffer(self.ibo.slice(..), wgpu::IndexFormat::Uint32);
-        render_pass.draw_indexed(0..indices as u32, 0, 0..1);
 
-        super::RenderInfo { draw_calls: 1, vertices }
+        let mut draw_calls = 0;
+
+        for batch in self.batches.batches.drain(..) {
+            let (min, max) = batch.clip.map_or((glam::UVec2::ZERO, window_size), |clip| {
+                let min = clip.origin.max(glam::Vec2::ZERO).as_uvec2().min(window_size);
+                let max = (clip.origin + clip.size).max(glam::Vec2::ZERO).ceil().as_uvec2().min(window_size);
+
+                (min, max)
+            });
+
+            if min.x >= max.x || min.y >= max.y {
+                continue;
+            }
+
+            render_pass.set_scissor_rect(min.x, min.y, max.x - min.x, max.y - min.y);
+            render_pass.draw_indexed(batch.indices, 0, 0..1);
+
+            draw_calls += 1;
+        }
+
+        render_pass.set_scissor_rect(0, 0, window_size.x, window_size.y);
+
+        super::RenderInfo { draw_calls, vertices }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mavelin_shared::{Color, RRect, Thickness};
+    use swash::FontRef;
+
+    use super::{BreakOpportunity, DrawBatches, FontMetrics, LineStyle, ShapedCluster, push_round_rect_geometry, transform_vertices, wrap_lines};
+    use crate::render::RawRenderBuffer;
+
+    fn word(chars: usize, advance: f32) -> Vec<ShapedCluster> {
+        (0..chars)
+            .map(|_| ShapedCluster {
+                advance,
+                glyphs: Vec::new(),
+                opportunity: BreakOpportunity::NoBreak,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_monocraft_line_height() {
+        let font = FontRef::from_index(include_bytes!("../../../../resources/fonts/Monocraft.ttf"), 0).unwrap();
+        let metrics = FontMetrics::new(font, 18.0);
+
+        assert!(metrics.ascent > 0.0 && metrics.descent > 0.0);
+        assert!((metrics.ascent + metrics.descent + metrics.line_gap - metrics.line_height()).abs() < f32::EPSILON);
+        assert!(metrics.line_height() > 18.0);
+    }
+
+    #[test]
+    fn test_long_word_breaks_between_characters() {
+        let clusters = word(30, 10.0);
+        let lines = wrap_lines(&clusters, Some(200.0), 5.0);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].clusters, 0..20);
+        assert_eq!(lines[1].clusters, 20..30);
+        assert!(lines.iter().all(|line| line.width <= 200.0));
+    }
+
+    #[test]
+    fn test_prefers_spaces_and_soft_hyphens() {
+        let mut clusters = word(8, 10.0);
+
+        clusters[3].opportunity = BreakOpportunity::Space;
+
+        let lines = wrap_lines(&clusters, Some(60.0), 5.0);
+
+        assert_eq!(lines[0].clusters, 0..4);
+        assert!((lines[0].width - 30.0).abs() < f32::EPSILON);
+
+        clusters[3].opportunity = BreakOpportunity::SoftHyphen;
+
+        let lines = wrap_lines(&clusters, Some(60.0), 5.0);
+
+        assert_eq!(lines[0].clusters, 0..4);
+        assert!(lines[0].hyphen);
+    }
+
+    #[test]
+    fn test_mandatory_breaks_without_max_width() {
+        let mut clusters = word(5, 10.0);
+
+        clusters[2].opportunity = BreakOpportunity::Mandatory;
+
+        let lines = wrap_lines(&clusters, None, 5.0);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].clusters, 0..2);
+        assert_eq!(lines[1].clusters, 3..5);
+    }
+
+    #[test]
+    fn test_dashes_cover_line_length() {
+        let style = LineStyle { dash_len: 4.0, gap_len: 2.0 };
+        let dashes = style.dashes(glam::Vec2::ZERO, glam::Vec2::new(15.0, 0.0));
+
+        assert_eq!(dashes, [
+            (glam::Vec2::new(0.0, 0.0), glam::Vec2::new(4.0, 0.0)),
+            (glam::Vec2::new(6.0, 0.0), glam::Vec2::new(10.0, 0.0)),
+            (glam::Vec2::new(12.0, 0.0), glam::Vec2::new(15.0, 0.0)),
+        ]);
+    }
+
+    #[test]
+    fn test_zero_length_line_has_no_dashes() {
+        assert!(LineStyle::DASHED.dashes(glam::Vec2::ONE, glam::Vec2::ONE).is_empty());
+    }
+
+    #[test]
+    fn test_tessellated_round_rects_share_draw_call() {
+        let mut buffers = RawRenderBuffer::new();
+        let mut batches = DrawBatches::default();
+
+        push_round_rect_geometry(
+            &mut buffers,
+            RRect::new(glam::Vec2::ZERO, glam::Vec2::splat(10.0), Thickness::all(2.0)),
+            4,
+            Color::WHITE,
+        );
+        push_round_rect_geometry(
+            &mut buffers,
+            RRect::new(glam::Vec2::new(20.0, 0.0), glam::Vec2::splat(10.0), Thickness::all(4.0)),
+            4,
+            Color::RED,
+        );
+        batches.flush(buffers.indices.len() as u32, None, 0);
+        batches.sort_by_layer(&mut buffers.indices);
+
+        assert_eq!(batches.batches.len(), 1);
+        assert_eq!(batches.batches[0].indices, 0..buffers.indices.len() as u32);
+        assert!(buffers.indices.iter().all(|&index| (index as usize) < buffers.vertices.len()));
+    }
+
+    #[test]
+    fn test_nested_transforms_compose() {
+        let outer = glam::Mat4::from_scale(glam::Vec3::new(2.0, 3.0, 1.0));
+        let inner = glam::Mat4::from_rotation_z(30f32.to_radians());
+        let mut buffers = RawRenderBuffer::new();
+
+        push_round_rect_geometry(
+            &mut buffers,
+            RRect::new(glam::Vec2::new(5.0, 1.0), glam::Vec2::splat(10.0), Thickness::all(2.0)),
+            2,
+            Color::WHITE,
+        );
+
+        let original = buffers.vertices.clone();
+
+        // What `transformed(outer, |r| r.transformed(inner, ..))` does once
+        // both closures return.
+        transform_vertices(&mut buffers.vertices, inner);
+        transform_vertices(&mut buffers.vertices, outer);
+
+        for (vertex, original) in buffers.vertices.iter().zip(&original) {
+            let expected = (outer * inner).transform_point3(original.position.extend(0.0)).truncate();
+
+            assert!(vertex.position.distance(expected) < 1e-4);
+        }
     }
 }
@@ -1,4 +1,4 @@
-use mavelin_shared::{Color, Rect, Thickness};
+use mavelin_shared::{Axis, Color, Rect, Thickness};
 
 use crate::render::common::CommonRenderer;
 
@@ -13,19 +13,49 @@ pub trait MeasureStrategy {
 
 pub struct RowStrategy {
     spacing: f32,
+    v_arrangement: Arrangement,
+}
+
+impl RowStrategy {
+    /// Returns how far the shared baseline lies below the top of the row and
+    /// how much room is needed under it, or `None` if no child has a
+    /// baseline.
+    fn baseline_extents(context: &UiContext, widget: WidgetId) -> Option<(f32, f32)> {
+        widget
+            .into_iter(context.all_children(widget))
+            .filter(|&w| context.parent(w) == widget && !context.widgets[w.0].abs_pos)
+            .filter_map(|w| context.baseline(w).map(|baseline| (baseline, context.layout_node(w).size.y - baseline)))
+            .reduce(|(ascent, descent), (baseline, below)| (ascent.max(baseline), descent.max(below)))
+    }
 }
 
 impl ArrangeStrategy for RowStrategy {
     fn arrange(&mut self, context: &mut UiContext, widget: WidgetId) {
-        let mut offset = glam::Vec2::ZERO;
+        let height = context.layout_node(widget).size.y;
+        let baseline = context.baseline(widget);
+        let mut offset = 0.0;
 
         for w in widget.into_iter(context.all_children(widget)) {
             if context.parent(w) == widget && !context.widgets[w.0].abs_pos {
                 let item_size = context.layout_node(w).size;
+                let y = match self.v_arrangement {
+                    Arrangement::Start => 0.0,
+                    Arrangement::Center => (height - item_size.y) / 2.0,
+                    Arrangement::End => height - item_size.y,
+                    Arrangement::Stretch => {
+                        context.layout_node_mut(w).size.y = height;
+
+                        0.0
+                    }
+                    Arrangement::Baseline => match (baseline, context.baseline(w)) {
+                        (Some(baseline), Some(item_baseline)) => baseline - item_baseline,
+                        _ => (height - item_size.y) / 2.0,
+                    },
+                };
 
-                context.translate(w, offset);
+                context.translate(w, glam::Vec2::new(offset, y));
 
-                offset += glam::Vec2::new(item_size.x + self.spacing, 0.0);
+                offset += item_size.x + self.spacing;
             }
         }
     }
@@ -33,31 +63,32 @@ impl ArrangeStrategy for RowStrategy {
 
 impl MeasureStrategy for RowStrategy {
     fn measure(&mut self, context: &mut UiContext, widget: WidgetId) -> glam::Vec2 {
-        let mut size = glam::Vec2::ZERO;
+        let mut size = context.measure_stack(widget, Axis::X, self.spacing);
 
-        for w in widget.into_iter(context.all_children(widget)) {
-            if context.parent(w) == widget && !context.widgets[w.0].abs_pos {
-                let item_size = context.layout_node(w).size;
+        if matches!(self.v_arrangement, Arrangement::Baseline) {
+            let extents = Self::baseline_extents(context, widget);
 
-                size.y = size.y.max(item_size.y);
-                size.x += item_size.x + self.spacing;
+            if let Some((ascent, descent)) = extents {
+                size.y = size.y.max(ascent + descent);
             }
-        }
 
-        if size.x > 0.0 {
-            size.x -= self.spacing;
+            context.widgets[widget.0].baseline = extents.map(|(ascent, _)| ascent);
         }
 
         size
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum Arrangement {
     Start,
     Center,
     End,
     Stretch,
+    /// Lines children up on the baseline of their first text line. Only rows
+    /// support it, children without a baseline are centered instead.
+    Baseline,
 }
 
 pub struct ColumnStrategy {
@@ -118,29 +149,14 @@ impl ArrangeStrategy for ColumnStrategy {
                     }
                 }
             }
-            Arrangement::Start => (),
+            Arrangement::Start | Arrangement::Baseline => (),
         }
     }
 }
 
 impl MeasureStrategy for ColumnStrategy {
     fn measure(&mut self, context: &mut UiContext, widget: WidgetId) -> glam::Vec2 {
-        let mut size = glam::Vec2::ZERO;
-
-        for w in widget.into_iter(context.all_children(widget)) {
-            if context.parent(w) == widget && !context.widgets[w.0].abs_pos {
-                let item_size = context.layout_node(w).size;
-
-                size.x = size.x.max(item_size.x);
-                size.y += item_size.y + self.spacing;
-            }
-        }
-
-        if size.y > 0.0 {
-            size.y -= self.spacing;
-        }
-
-        size
+        context.measure_stack(widget, Axis::Y, self.spacing)
     }
 }
 
@@ -231,7 +247,14 @@ pub struct WidgetState {
 struct WidgetData {
     parent: WidgetId,
     layout_node: Rect,
+    /// Offset of the baseline from the top of `layout_node`, if the widget
+    /// has one.
+    baseline: Option<f32>,
+    /// Applied around the center of `layout_node` when painting the widget
+    /// and its descendants.
+    transform: Option<glam::Mat4>,
     abs_pos: bool,
+    clip: bool,
     children: usize,
     shape: Shape,
     state: WidgetState,
@@ -253,7 +276,10 @@ impl UiContext {
             widgets: vec![WidgetData {
                 parent: WidgetId(0),
                 layout_node: Rect::ZERO,
+                baseline: None,
+                transform: None,
                 abs_pos: false,
+                clip: false,
                 children: 0,
                 shape: Shape::Noop,
                 state: WidgetState::default(),
@@ -325,6 +351,46 @@ impl UiContext {
         &mut self.widgets[widget.0].layout_node
     }
 
+    pub fn baseline(&self, widget: WidgetId) -> Option<f32> {
+        self.widgets[widget.0].baseline
+    }
+
+    pub fn set_baseline(&mut self, widget: WidgetId, baseline: Option<f32>) {
+        self.widgets[widget.0].baseline = baseline;
+    }
+
+    /// Measures the direct children of `widget` placed one after another
+    /// along `direction`, `spacing` apart. Absolutely positioned children
+    /// don't take up any space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `direction` is [`Axis::Z`].
+    pub fn measure_stack(&self, widget: WidgetId, direction: Axis, spacing: f32) -> glam::Vec2 {
+        let (main, cross) = match direction {
+            Axis::X => (0, 1),
+            Axis::Y => (1, 0),
+            Axis::Z => panic!("UI widgets can't be stacked along the Z axis"),
+        };
+
+        let mut size = glam::Vec2::ZERO;
+
+        for w in widget.into_iter(self.all_children(widget)) {
+            if self.parent(w) == widget && !self.widgets[w.0].abs_pos {
+                let item_size = self.layout_node(w).size;
+
+                size[cross] = size[cross].max(item_size[cross]);
+                size[main] += item_size[main] + spacing;
+            }
+        }
+
+        if size[main] > 0.0 {
+            size[main] -= spacing;
+        }
+
+        size
+    }
+
     pub fn all_children(&self, widget: WidgetId) -> usize {
         self.widgets[widget.0].children
     }
@@ -340,10 +406,68 @@ impl UiContext {
     pub fn paint(&self, renderer: &mut CommonRenderer, queue: &wgpu::Queue, widget: WidgetId) {
         let data = &self.widgets[widget.0];
 
-        data.shape.paint(renderer, queue, data.layout_node);
+        if let Some(matrix) = data.transform {
+            let center = (data.layout_node.origin + data.layout_node.size / 2.0).extend(0.0);
+            let matrix = glam::Mat4::from_translation(center) * matrix * glam::Mat4::from_translation(-center);
 
-        for w in 1..=self.all_children(widget) {
-            self.paint(renderer, queue, WidgetId(widget.0 + w));
+            renderer.transformed(matrix, |renderer| self.paint_untransformed(renderer, queue, widget));
+        } else {
+            self.paint_untransformed(renderer, queue, widget);
+        }
+    }
+
+    fn paint_untransformed(&self, renderer: &mut CommonRenderer, queue: &wgpu::Queue, widget: WidgetId) {
+        let data = &self.widgets[widget.0];
+        let visible = renderer.is_visible(data.layout_node);
+
+        // Children of a clipped widget can't show up outside of it either.
+        if data.clip && !visible {
+            return;
+        }
+
+        if data.clip {
+            renderer.push_clip(data.layout_node);
+        }
+
+        if visible {
+            data.shape.paint(renderer, queue, data.layout_node);
+        }
+
+        let mut w = 1;
+
+        while w <= self.all_children(widget) {
+            let child = WidgetId(widget.0 + w);
+
+            self.paint(renderer, queue, child);
+
+            w += self.all_children(child) + 1;
+        }
+
+        if data.clip {
+            renderer.pop_clip();
+        }
+    }
+
+    /// Strokes the layout bounds of every widget on top of the painted UI,
+    /// using a different hue for each nesting depth.
+    pub fn paint_debug_outlines(&self, renderer: &mut CommonRenderer) {
+        self.paint_debug_outline(renderer, WidgetId(0), 0);
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn paint_debug_outline(&self, renderer: &mut CommonRenderer, widget: WidgetId, depth: usize) {
+        let node = self.layout_node(widget);
+
+        renderer.draw_rect_outline(node.origin, node.size, 1.0, Color::from_hsl((depth * 47 % 360) as f32, 0.8, 0.6));
+
+        let mut w = 1;
+
+        while w <= self.all_children(widget) {
+            let child = WidgetId(widget.0 + w);
+
+            self.paint_debug_outline(renderer, child, depth + 1);
+
+            w += self.all_children(child) + 1;
         }
     }
 
@@ -357,7 +481,10 @@ impl UiContext {
             self.widgets.push(WidgetData {
                 parent,
                 layout_node: Rect::new(glam::Vec2::ZERO, size),
+                baseline: None,
+                transform: None,
                 abs_pos: false,
+                clip: false,
                 children: 0,
                 shape,
                 state: WidgetState::default(),
@@ -365,7 +492,10 @@ impl UiContext {
         } else {
             self.widgets[id.0].parent = parent;
             self.widgets[id.0].layout_node = Rect::new(glam::Vec2::ZERO, size);
+            self.widgets[id.0].baseline = None;
+            self.widgets[id.0].transform = None;
             self.widgets[id.0].abs_pos = false;
+            self.widgets[id.0].clip = false;
             self.widgets[id.0].children = 0;
             self.widgets[id.0].shape = shape;
         }
@@ -374,7 +504,10 @@ impl UiContext {
     pub fn root<'a>(&'a mut self, renderer: &'a CommonRenderer, size: glam::Vec2) -> UiSubcontext<'a, RowStrategy, RowStrategy> {
         self.widgets[0].layout_node = Rect::new(glam::Vec2::ZERO, size);
         self.widgets[0].children = 0;
+        self.widgets[0].baseline = None;
+        self.widgets[0].transform = None;
         self.widgets[0].abs_pos = false;
+        self.widgets[0].clip = false;
         self.widgets[0].shape = Shape::Noop;
 
         UiSubcontext {
@@ -382,13 +515,34 @@ impl UiContext {
             next_child_id: WidgetId(1),
             context: self,
             renderer,
-            arrange_strategy: RowStrategy { spacing: 0.0 },
-            measure_strategy: RowStrategy { spacing: 0.0 },
+            arrange_strategy: RowStrategy {
+                spacing: 0.0,
+                v_arrangement: Arrangement::Start,
+            },
+            measure_strategy: RowStrategy {
+                spacing: 0.0,
+                v_arrangement: Arrangement::Start,
+            },
             explicit_pos: None,
             explicit_height: None,
             explicit_width: None,
         }
     }
+
+    /// Same as [`Self::root`], but shrinks the available area by `insets`,
+    /// keeping content clear of title bars or rounded screen corners.
+    #[allow(dead_code)]
+    pub fn root_inset<'a>(&'a mut self, renderer: &'a CommonRenderer, size: glam::Vec2, insets: Thickness) -> UiSubcontext<'a, RowStrategy, RowStrategy> {
+        let origin = glam::Vec2::new(insets.left(), insets.top());
+        let size = (size - origin - glam::Vec2::new(insets.right(), insets.bottom())).max(glam::Vec2::ZERO);
+        let mut root = self.root(renderer, size);
+
+        root.explicit_pos = Some(origin);
+        root.explicit_width = Some(size.x);
+        root.explicit_height = Some(size.y);
+
+        root
+    }
 }
 
 pub struct UiSubcontext<'a, A: ArrangeStrategy, M: MeasureStrategy> {
@@ -404,6 +558,12 @@ pub struct UiSubcontext<'a, A: ArrangeStrategy, M: MeasureStrategy> {
 }
 
 impl UiSubcontext<'_, RowStrategy, RowStrategy> {
+    #[allow(dead_code)]
+    pub const fn set_v_arrangement(&mut self, arrangement: Arrangement) {
+        self.arrange_strategy.v_arrangement = arrangement;
+        self.measure_strategy.v_arrangement = arrangement;
+    }
+
     #[allow(dead_code)]
     pub const fn set_spacing(&mut self, pixels: f32) {
         self.arrange_strategy.spacing = pixels;
@@ -515,6 +675,19 @@ impl<A: ArrangeStrategy, M: MeasureStrategy> UiSubcontext<'_, A, M> {
         self.context.widgets[self.id.0].shape = Shape::Rect(color);
     }
 
+    /// Scales, rotates or otherwise transforms this widget and all of its
+    /// descendants around its center when painted. Layout is unaffected, and
+    /// transforms of nested widgets compose.
+    pub fn set_transform(&mut self, matrix: glam::Mat4) {
+        self.context.widgets[self.id.0].transform = Some(matrix);
+    }
+
+    /// Clips this widget and all of its descendants to its layout bounds.
+    #[allow(dead_code)]
+    pub fn set_clipped(&mut self, clipped: bool) {
+        self.context.widgets[self.id.0].clip = clipped;
+    }
+
     #[allow(dead_code)]
     pub fn set_rounding(&mut self, thickness: Thickness, color: Color) {
         self.context.widgets[self.id.0].shape = Shape::RRect(thickness, color);
@@ -551,7 +724,17 @@ impl<A: ArrangeStrategy, M: MeasureStrategy> UiSubcontext<'_, A, M> {
     }
 
     pub fn row(&mut self, ui: impl FnOnce(&mut UiSubcontext<'_, RowStrategy, RowStrategy>)) {
-        self.scope(RowStrategy { spacing: 0.0 }, RowStrategy { spacing: 0.0 }, ui);
+        self.scope(
+            RowStrategy {
+                spacing: 0.0,
+                v_arrangement: Arrangement::Start,
+            },
+            RowStrategy {
+                spacing: 0.0,
+                v_arrangement: Arrangement::Start,
+            },
+            ui,
+        );
     }
 
     pub fn column(&mut self, ui: impl FnOnce(&mut UiSubcontext<'_, ColumnStrategy, ColumnStrategy>)) {
@@ -585,8 +768,10 @@ impl<A: ArrangeStrategy, M: MeasureStrategy> UiSubcontext<'_, A, M> {
     pub fn text<T: Into<String>>(&mut self, text: T, font_size: f32, font: &'static str, color: Color) {
         let text = text.into();
         let size = self.renderer.measure(font, &text, font_size, None).unwrap_or_default();
+        let baseline = self.renderer.font_metrics(font, font_size).map(|metrics| metrics.ascent);
+        let id = self.sized_child(size, Shape::Text(text, font_size, font, color));
 
-        self.sized_child(size, Shape::Text(text, font_size, font, color));
+        self.context.set_baseline(id, baseline);
     }
 
     #[allow(dead_code)]
@@ -600,3 +785,41 @@ impl<A: ArrangeStrategy, M: MeasureStrategy> Drop for UiSubcontext<'_, A, M> {
         self.perform_layout();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use swash::FontRef;
+
+    use super::{ArrangeStrategy, Arrangement, MeasureStrategy, RowStrategy, Shape, UiContext, WidgetId};
+    use crate::render::common::FontMetrics;
+
+    #[test]
+    fn test_row_aligns_text_baselines() {
+        let font = FontRef::from_index(include_bytes!("../../../../resources/fonts/Monocraft.ttf"), 0).unwrap();
+        let row = WidgetId(1);
+        let mut context = UiContext::new();
+
+        context.try_allocate_widget(WidgetId(0), row, Shape::Noop, glam::Vec2::ZERO);
+
+        for (id, size) in [(WidgetId(2), 9.0), (WidgetId(3), 24.0)] {
+            let metrics = FontMetrics::new(font, size);
+
+            context.try_allocate_widget(row, id, Shape::Noop, glam::Vec2::new(size, metrics.ascent + metrics.descent));
+            context.set_baseline(id, Some(metrics.ascent));
+        }
+
+        let mut strategy = RowStrategy {
+            spacing: 0.0,
+            v_arrangement: Arrangement::Baseline,
+        };
+        let size = strategy.measure(&mut context, row);
+
+        context.set_size(row, size);
+        strategy.arrange(&mut context, row);
+
+        let [small, large] = [WidgetId(2), WidgetId(3)].map(|id| context.layout_node(id).origin.y + context.baseline(id).unwrap());
+
+        assert!((small - large).abs() < f32::EPSILON);
+        assert!((large - FontMetrics::new(font, 24.0).ascent).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,199 @@
+use ahash::{HashMap, HashMapExt};
+use image::{Rgba, RgbaImage};
+use mavelin_shared::{AsValue, Color, Face};
+use mavelin_storage::ResourceStorage;
+use mavelin_world::{ChunkAccess, ChunkCache, ChunkManager};
+
+use crate::render::common::{CommonRenderer, ImageId};
+
+/// Top-down map of the surface around the player, one pixel per block column.
+///
+/// Columns are resampled a fixed amount at a time in [`Minimap::update`], so
+/// the cost per frame stays bounded no matter how far the player moves.
+pub struct Minimap {
+    image: RgbaImage,
+    center: glam::IVec2,
+    next_column: u32,
+    block_colors: HashMap<u32, Color>,
+    image_id: Option<ImageId>,
+    dirty: bool,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self {
+            image: RgbaImage::new(Self::SIZE, Self::SIZE),
+            center: glam::IVec2::ZERO,
+            next_column: 0,
+            block_colors: HashMap::new(),
+            image_id: None,
+            dirty: true,
+        }
+    }
+}
+
+impl Minimap {
+    pub const COLUMNS_PER_FRAME: u32 = 512;
+    pub const SIZE: u32 = 64;
+
+    /// Moves the map so that `center` is in its middle. Already sampled
+    /// columns are kept, only the newly uncovered ones are cleared.
+    pub fn set_center(&mut self, center: glam::IVec2) {
+        let offset = center - self.center;
+
+        if offset == glam::IVec2::ZERO {
+            return;
+        }
+
+        let size = Self::SIZE.cast_signed();
+        let mut image = RgbaImage::new(Self::SIZE, Self::SIZE);
+
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            let source = glam::IVec2::new(x.cast_signed(), y.cast_signed()) + offset;
+
+            if source.cmpge(glam::IVec2::ZERO).all() && source.cmplt(glam::IVec2::splat(size)).all() {
+                *pixel = *self.image.get_pixel(source.x.cast_unsigned(), source.y.cast_unsigned());
+            }
+        }
+
+        self.image = image;
+        self.center = center;
+        self.dirty = true;
+    }
+
+    /// Forgets the cached block colors and starts resampling from the first
+    /// column, call it after block models or textures were reloaded.
+    pub fn invalidate(&mut self) {
+        self.block_colors.clear();
+        self.next_column = 0;
+        self.dirty = true;
+    }
+
+    /// Resamples the next [`Self::COLUMNS_PER_FRAME`] columns, wrapping around
+    /// once the whole map has been visited.
+    pub fn update<C: ChunkCache>(&mut self, chunk_manager: &ChunkManager<C>, storage: &ResourceStorage) {
+        let half_size = (Self::SIZE / 2).cast_signed();
+
+        for _ in 0..Self::COLUMNS_PER_FRAME {
+            let x = self.next_column % Self::SIZE;
+            let y = self.next_column / Self::SIZE;
+            let column = self.center + glam::IVec2::new(x.cast_signed(), y.cast_signed()) - half_size;
+
+            let top = chunk_manager
+                .highest_block(column.x, column.y)
+                .and_then(|position| chunk_manager.get_block(position).map(|block| (position, block.id)));
+
+            let color = top.map_or(Color::default(), |(position, block)| {
+                let color = *self.block_colors.entry(block).or_insert_with(|| Self::average_top_color(storage, block));
+                let tint = chunk_manager
+                    .get_biome(position)
+                    .and_then(|biome| storage.blocks.get(block).and_then(|block| block.tint_color(&storage.color_config, biome)));
+
+                tint.map_or(color, |tint| Self::multiply(color, tint))
+            });
+
+            let pixel = Rgba(color.as_value());
+
+            if *self.image.get_pixel(x, y) != pixel {
+                self.image.put_pixel(x, y, pixel);
+                self.dirty = true;
+            }
+
+            self.next_column = (self.next_column + 1) % (Self::SIZE * Self::SIZE);
+        }
+    }
+
+    pub fn draw(&mut self, queue: &wgpu::Queue, renderer: &mut CommonRenderer, origin: glam::Vec2, size: glam::Vec2) {
+        let image_id = match self.image_id {
+            Some(image_id) if self.dirty => {
+                renderer.update_image(queue, image_id, &self.image);
+
+                image_id
+            }
+            Some(image_id) => image_id,
+            None => {
+                let Some(image_id) = renderer.upload_image(queue, &self.image) else {
+                    return;
+                };
+
+                self.image_id = Some(image_id);
+
+                image_id
+            }
+        };
+
+        self.dirty = false;
+
+        renderer.draw_rect(origin, size, Color::BLACK.with_alpha(0.5));
+        renderer.draw_image(image_id, origin, size, Color::WHITE);
+        renderer.draw_rect(origin + size / 2.0 - glam::Vec2::splat(1.0), glam::Vec2::splat(2.0), Color::RED);
+    }
+
+    /// Frees the map's image in the renderer atlas, call it before dropping
+    /// the world that owns the map.
+    pub fn release(&mut self, renderer: &mut CommonRenderer) {
+        if let Some(image_id) = self.image_id.take() {
+            renderer.remove_image(image_id);
+        }
+
+        self.dirty = true;
+    }
+
+    fn multiply(color: Color, tint: Color) -> Color {
+        let [red, green, blue, alpha]: [u8; 4] = color.as_value();
+        let [tint_red, tint_green, tint_blue, _]: [u8; 4] = tint.as_value();
+
+        Color::new(
+            ((u16::from(red) * u16::from(tint_red)) / 255) as u8,
+            ((u16::from(green) * u16::from(tint_green)) / 255) as u8,
+            ((u16::from(blue) * u16::from(tint_blue)) / 255) as u8,
+            alpha,
+        )
+    }
+
+    /// Averages the non-transparent texels of the block's top face texture.
+    fn average_top_color(storage: &ResourceStorage, block: u32) -> Color {
+        let model = storage.models.get_unchecked(storage.blocks.get_model_by_name(block));
+        let Some(face) = model
+            .elements
+            .iter()
+            .flat_map(|element| &element.faces)
+            .find(|face| face.face_data.face == Face::Top)
+        else {
+            return Color::default();
+        };
+
+        let atlas = storage.get_texture_atlas();
+        let atlas_size = glam::Vec2::new(atlas.width() as f32, atlas.height() as f32);
+        let (min, max) = face
+            .face_data
+            .uvs
+            .iter()
+            .fold((glam::Vec2::MAX, glam::Vec2::MIN), |(min, max), &uv| (min.min(uv), max.max(uv)));
+
+        let min = (min * atlas_size).as_uvec2().min(glam::UVec2::new(atlas.width(), atlas.height()) - 1);
+        let max = (max * atlas_size).as_uvec2().max(min + 1).min(glam::UVec2::new(atlas.width(), atlas.height()));
+
+        let mut sum = [0u64; 3];
+        let mut count = 0u64;
+
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let Rgba([red, green, blue, alpha]) = *atlas.get_pixel(x, y);
+
+                if alpha > 0 {
+                    sum[0] += u64::from(red);
+                    sum[1] += u64::from(green);
+                    sum[2] += u64::from(blue);
+                    count += 1;
+                }
+            }
+        }
+
+        if count == 0 {
+            Color::default()
+        } else {
+            Color::new((sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8, 255)
+        }
+    }
+}
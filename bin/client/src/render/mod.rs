@@ -1,10 +1,14 @@
 use core::fmt;
+use std::{collections::VecDeque, time::Duration};
 
+use serde::{Deserialize, Serialize};
 use wgpu::util::DeviceExt;
 
 pub mod chunk;
 pub mod common;
 pub mod context;
+pub mod minimap;
+pub mod texture_animation;
 
 #[derive(Debug, Clone, Copy)]
 pub struct RenderInfo {
@@ -34,7 +38,99 @@ impl RenderInfo {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Minimum, average and maximum of a value over a [`RenderStats`] window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StatRange<T> {
+    pub min: T,
+    pub avg: T,
+    pub max: T,
+}
+
+/// Rolling window over the [`RenderInfo`] and frame time of the last frames,
+/// steadier to read than the values of a single frame.
+#[derive(Debug, Clone)]
+pub struct RenderStats {
+    window: usize,
+    frames: VecDeque<(RenderInfo, Duration)>,
+}
+
+impl Default for RenderStats {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_WINDOW)
+    }
+}
+
+impl RenderStats {
+    pub const DEFAULT_WINDOW: usize = 120;
+
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            frames: VecDeque::with_capacity(window.max(1)),
+        }
+    }
+
+    /// Changes how many frames are kept, dropping the oldest ones if the
+    /// window shrinks.
+    #[allow(dead_code)]
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(1);
+
+        while self.frames.len() > self.window {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Records a finished frame, `info` being what [`RenderInfo::take`]
+    /// returned for it.
+    pub fn push(&mut self, info: RenderInfo, frame_time: Duration) {
+        if self.frames.len() >= self.window {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back((info, frame_time));
+    }
+
+    pub fn draw_calls(&self) -> StatRange<usize> {
+        self.range(|(info, _)| info.draw_calls)
+    }
+
+    pub fn vertices(&self) -> StatRange<usize> {
+        self.range(|(info, _)| info.vertices)
+    }
+
+    #[allow(dead_code)]
+    pub fn frame_time(&self) -> StatRange<Duration> {
+        let nanos = self.range(|(_, frame_time)| frame_time.as_nanos() as usize);
+
+        StatRange {
+            min: Duration::from_nanos(nanos.min as u64),
+            avg: Duration::from_nanos(nanos.avg as u64),
+            max: Duration::from_nanos(nanos.max as u64),
+        }
+    }
+
+    fn range(&self, value: impl Fn(&(RenderInfo, Duration)) -> usize) -> StatRange<usize> {
+        if self.frames.is_empty() {
+            return StatRange::default();
+        }
+
+        let (min, max, sum) = self
+            .frames
+            .iter()
+            .map(value)
+            .fold((usize::MAX, 0, 0), |(min, max, sum), value| (min.min(value), max.max(value), sum + value));
+
+        StatRange {
+            min,
+            avg: sum / self.frames.len(),
+            max,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 #[allow(dead_code)]
 pub enum RenderShape {
     Circle(u16),
@@ -174,6 +270,15 @@ impl RenderShape {
         }
     }
 
+    /// Distance in chunks from the center to the nearest edge of the shape.
+    pub const fn radius(self) -> u16 {
+        match self {
+            Self::Circle(r) => r,
+            Self::Rect(w, h) => (if w < h { w } else { h }) / 2,
+            Self::Square(s) => s / 2,
+        }
+    }
+
     pub const fn enlarge(self, amount: u16) -> Self {
         match self {
             Self::Circle(r) => Self::Circle(r + amount),
@@ -211,6 +316,31 @@ impl RenderBuffer {
         }
     }
 
+    /// Overwrites the contents of a buffer created with [`Self::new`] in
+    /// place, only allocating new buffers when the data doesn't fit into the
+    /// old ones.
+    pub fn replace<V: bytemuck::NoUninit>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        vertices: &[V],
+        v_label: &str,
+        indices: &[u32],
+        i_label: &str,
+    ) {
+        let vertex_bytes: &[u8] = bytemuck::cast_slice(vertices);
+        let index_bytes: &[u8] = bytemuck::cast_slice(indices);
+
+        if vertex_bytes.len() as u64 > self.vertices.size() || index_bytes.len() as u64 > self.indices.size() {
+            *self = Self::new(device, vertices, v_label, indices, i_label);
+        } else {
+            queue.write_buffer(&self.vertices, 0, vertex_bytes);
+            queue.write_buffer(&self.indices, 0, index_bytes);
+
+            self.count = indices.len();
+        }
+    }
+
     #[inline]
     pub fn new_dynamic<V: bytemuck::NoUninit>(device: &wgpu::Device, vertices: &[V], v_label: &str, indices: &[u32], i_label: &str) -> Self {
         Self {
@@ -265,3 +395,42 @@ impl<V: bytemuck::NoUninit> Default for RawRenderBuffer<V> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{RenderInfo, RenderStats, StatRange};
+
+    fn frame(draw_calls: usize, millis: u64) -> (RenderInfo, Duration) {
+        (
+            RenderInfo {
+                draw_calls,
+                vertices: draw_calls * 4,
+            },
+            Duration::from_millis(millis),
+        )
+    }
+
+    #[test]
+    fn test_render_stats_window() {
+        let mut stats = RenderStats::new(3);
+
+        for (info, frame_time) in [frame(100, 40), frame(10, 10), frame(20, 20), frame(30, 30)] {
+            stats.push(info, frame_time);
+        }
+
+        assert_eq!(stats.draw_calls(), StatRange { min: 10, avg: 20, max: 30 });
+        assert_eq!(stats.vertices(), StatRange { min: 40, avg: 80, max: 120 });
+        assert_eq!(stats.frame_time().max, Duration::from_millis(30));
+
+        stats.set_window(1);
+
+        assert_eq!(stats.draw_calls(), StatRange { min: 30, avg: 30, max: 30 });
+    }
+
+    #[test]
+    fn test_empty_render_stats() {
+        assert_eq!(RenderStats::default().draw_calls(), StatRange::default());
+    }
+}
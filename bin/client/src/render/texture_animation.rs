@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use mavelin_storage::{ResourceStorage, TextureAnimation};
+
+/// Plays animated block textures by copying their current frame over the
+/// first one in the GPU atlas, which is the frame faces are meshed with.
+#[derive(Default)]
+pub struct TextureAnimations {
+    /// Every animation with the frame currently uploaded for it.
+    animations: Vec<(TextureAnimation, u32)>,
+    elapsed: Duration,
+}
+
+impl TextureAnimations {
+    /// Picks up the animations of the currently loaded block models, call it
+    /// whenever the models change.
+    pub fn reset(&mut self, storage: &ResourceStorage) {
+        self.animations = storage.models.texture_animations().into_iter().map(|animation| (animation, 0)).collect();
+    }
+
+    /// Advances all animations by `delta` and uploads the frames that changed
+    /// to every mip level of `texture`.
+    pub fn update(&mut self, queue: &wgpu::Queue, texture: &wgpu::Texture, storage: &ResourceStorage, delta: Duration) {
+        self.elapsed += delta;
+
+        for (animation, current) in &mut self.animations {
+            let frame = animation.frame_at(self.elapsed);
+
+            if frame == *current {
+                continue;
+            }
+
+            *current = frame;
+
+            for (level, image) in storage.get_mipmaps().iter().enumerate() {
+                let source = animation.frame_origin(frame) >> level as u32;
+                let target = animation.frame_origin(0) >> level as u32;
+                let size = animation.frame_size >> level as u32;
+
+                if size.min_element() == 0 {
+                    break;
+                }
+
+                queue.write_texture(
+                    wgpu::TexelCopyTextureInfoBase {
+                        texture,
+                        mip_level: level as u32,
+                        origin: wgpu::Origin3d {
+                            x: target.x,
+                            y: target.y,
+                            z: 0,
+                        },
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    image.as_raw(),
+                    wgpu::TexelCopyBufferLayout {
+                        offset: u64::from((source.y * image.width() + source.x) * 4),
+                        bytes_per_row: Some(4 * image.width()),
+                        rows_per_image: Some(size.y),
+                    },
+                    wgpu::Extent3d {
+                        width: size.x,
+                        height: size.y,
+                        depth_or_array_layers: 1,
+                    },
+                );
+            }
+        }
+    }
+}
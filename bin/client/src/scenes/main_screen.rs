@@ -24,6 +24,11 @@ fn menu_button<A: ArrangeStrategy, M: MeasureStrategy>(scope: &mut UiSubcontext<
     })
 }
 
+/// The title box is drawn slightly enlarged, with the subtitle tilted inside
+/// it like a splash text.
+const TITLE_SCALE: f32 = 1.1;
+const SUBTITLE_ANGLE: f32 = -8f32.to_radians();
+
 pub struct MainScreen;
 
 pub enum MainScreenAction {
@@ -44,9 +49,13 @@ impl Screen for MainScreen {
 
             scope.column(|scope| {
                 scope.set_h_arrangement(Arrangement::End);
+                scope.set_transform(glam::Mat4::from_scale(glam::Vec3::new(TITLE_SCALE, TITLE_SCALE, 1.0)));
 
                 scope.text("MAVELIN", 72.0, "default", Color::from_hsl(110.0, 0.4, 0.7));
-                scope.text("deltarune today!", 18.0, "default", Color::from_hsl(110.0, 0.3, 0.6));
+                scope.row(|scope| {
+                    scope.set_transform(glam::Mat4::from_rotation_z(SUBTITLE_ANGLE));
+                    scope.text("deltarune today!", 18.0, "default", Color::from_hsl(110.0, 0.3, 0.6));
+                });
             });
         });
 
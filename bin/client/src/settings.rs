@@ -1,37 +1,53 @@
-use std::{collections::VecDeque, time::Duration};
+use core::fmt;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs, io,
+    path::Path,
+    time::Duration,
+};
 
+use mavelin_engine::KeyCode;
+use mavelin_shared::Color;
 use mavelin_storage::ResourceStorage;
 use mavelin_world::{Chunk, ChunkAccess};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::{
-    render::{RenderInfo, RenderShape},
+    input::key_code_from_name,
+    render::{RenderInfo, RenderShape, RenderStats},
     util::vertex_ao,
 };
 
 #[derive(Debug, Clone)]
 pub struct Debugging {
     pub enabled: bool,
+    pub ui_outlines: bool,
     pub draw_calls_stat: VecDeque<usize>,
     pub draw_calls_max: usize,
     pub fps_stat: VecDeque<Duration>,
     pub fps_max: Duration,
     pub render_info: RenderInfo,
+    pub render_stats: RenderStats,
 }
 
 impl Default for Debugging {
     fn default() -> Self {
         Self {
             enabled: false,
+            ui_outlines: false,
             draw_calls_stat: VecDeque::new(),
             draw_calls_max: 0,
             fps_stat: VecDeque::new(),
             fps_max: Duration::ZERO,
             render_info: RenderInfo::default(),
+            render_stats: RenderStats::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 #[allow(dead_code)]
 pub enum LightStyle {
     Smooth,
@@ -152,12 +168,17 @@ impl LightStyle {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 #[allow(dead_code)]
 pub struct GraphicsSettings {
     pub light_style: LightStyle,
     pub render_shape: RenderShape,
     pub vsync: bool,
+    pub fullscreen: bool,
+    /// Segments per corner of tessellated UI rounded rectangles, [`None`]
+    /// draws them as anti-aliased SDF quads instead.
+    pub ui_corner_segments: Option<u32>,
 }
 
 impl Default for GraphicsSettings {
@@ -166,12 +187,214 @@ impl Default for GraphicsSettings {
             light_style: LightStyle::Smooth,
             render_shape: RenderShape::Circle(12),
             vsync: true,
+            fullscreen: false,
+            ui_corner_segments: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)]
+pub enum CrosshairStyle {
+    Hidden,
+    Plus,
+    Dot,
+    Circle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HudSettings {
+    pub crosshair: CrosshairStyle,
+    /// Width and height of the crosshair in pixels.
+    pub crosshair_size: f32,
+    pub crosshair_color: Color,
+    /// Crosshair color while looking at a block, [`None`] keeps
+    /// [`Self::crosshair_color`].
+    pub crosshair_target_color: Option<Color>,
+}
+
+impl Default for HudSettings {
+    fn default() -> Self {
+        Self {
+            crosshair: CrosshairStyle::Plus,
+            crosshair_size: 16.0,
+            crosshair_color: Color::WHITE.with_alpha(0.8),
+            crosshair_target_color: Some(Color::from_hsl(110.0, 0.5, 0.8)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlSettings {
+    /// Multiplier applied to mouse movement before it turns the camera.
+    pub mouse_sensitivity: f32,
+    /// Walks the player onto full-block ledges without jumping.
+    pub step_onto_ledges: bool,
+    /// Key names bound to each action, e.g. `"walk.forward" = "KeyW"`.
+    pub binds: HashMap<String, String>,
+}
+
+impl ControlSettings {
+    const DEFAULT_BINDS: [(&str, &str); 4] = [
+        ("walk.forward", "KeyW"),
+        ("walk.backward", "KeyS"),
+        ("walk.left", "KeyA"),
+        ("walk.right", "KeyD"),
+    ];
+
+    /// Resolves the bound key names, falling back to the default key for
+    /// actions missing from the file.
+    pub fn key_binds(&self) -> Vec<(String, KeyCode)> {
+        let mut binds = Self::default().binds;
+
+        binds.extend(self.binds.clone());
+        binds
+            .into_iter()
+            .filter_map(|(action, key)| {
+                let code = key_code_from_name(&key);
+
+                if code.is_none() {
+                    warn!(target: "client/settings", "Unknown key {key:?} bound to {action}");
+                }
+
+                code.map(|code| (action, code))
+            })
+            .collect()
+    }
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        Self {
+            mouse_sensitivity: 1.0,
+            step_onto_ledges: false,
+            binds: Self::DEFAULT_BINDS
+                .into_iter()
+                .map(|(action, key)| (action.to_owned(), key.to_owned()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to access settings file: {error}"),
+            Self::Parse(error) => write!(f, "failed to parse settings: {error}"),
+            Self::Serialize(error) => write!(f, "failed to serialize settings: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<io::Error> for SettingsError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub graphics: GraphicsSettings,
+    pub hud: HudSettings,
+    pub controls: ControlSettings,
+    #[serde(skip)]
     pub debugging: Debugging,
 }
+
+impl Settings {
+    pub const PATH: &str = "./settings.toml";
+
+    /// Reads settings from a TOML file, fields missing from it keep their
+    /// default values.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SettingsError> {
+        toml::from_str(&fs::read_to_string(path)?).map_err(SettingsError::Parse)
+    }
+
+    /// Same as [`Self::load`], but falls back to the defaults if the file is
+    /// missing or broken.
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Self {
+        match Self::load(path) {
+            Ok(settings) => settings,
+            Err(SettingsError::Io(error)) if error.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(error) => {
+                tracing::error!("{error}, using default settings");
+
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SettingsError> {
+        fs::write(path, toml::to_string_pretty(self).map_err(SettingsError::Serialize)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mavelin_engine::KeyCode;
+    use mavelin_shared::Color;
+
+    use super::{CrosshairStyle, LightStyle, Settings};
+    use crate::render::RenderShape;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let path = std::env::temp_dir().join(format!("mavelin-settings-{}.toml", std::process::id()));
+        let mut settings = Settings::default();
+
+        settings.graphics.light_style = LightStyle::Blocky;
+        settings.graphics.render_shape = RenderShape::Rect(8, 4);
+        settings.graphics.vsync = false;
+        settings.hud.crosshair = CrosshairStyle::Dot;
+        settings.hud.crosshair_color = Color::new(12, 34, 56, 78);
+        settings.controls.mouse_sensitivity = 2.5;
+        settings.controls.step_onto_ledges = true;
+        settings.controls.binds.insert("walk.forward".to_owned(), "ArrowUp".to_owned());
+        settings.save(&path).unwrap();
+
+        let loaded = Settings::load(&path).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.graphics, settings.graphics);
+        assert_eq!(loaded.hud, settings.hud);
+        assert_eq!(loaded.controls, settings.controls);
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings: Settings = toml::from_str("[graphics]\nvsync = false\n").unwrap();
+        let defaults = Settings::default();
+
+        assert!(!settings.graphics.vsync);
+        assert_eq!(settings.graphics.render_shape, defaults.graphics.render_shape);
+        assert_eq!(settings.hud, defaults.hud);
+        assert_eq!(settings.controls, defaults.controls);
+    }
+
+    #[test]
+    fn test_key_binds_fall_back_to_defaults() {
+        let settings: Settings = toml::from_str("[controls.binds]\n\"walk.left\" = \"ArrowLeft\"\n\"walk.right\" = \"NotAKey\"\n").unwrap();
+        let binds = settings.controls.key_binds();
+        let bound = |action: &str| binds.iter().find(|(name, _)| name == action).map(|&(_, key)| key);
+
+        assert_eq!(bound("walk.left"), Some(KeyCode::ArrowLeft));
+        assert_eq!(bound("walk.forward"), Some(KeyCode::KeyW));
+        assert_eq!(bound("walk.right"), None);
+    }
+}
@@ -153,9 +153,9 @@ pub fn get_sky_color((after_day, progress): (bool, f32), weather: f32) -> Color
     let night_color: Color = Color::from_hsl(220.0, 0.1f32.mul_add(weather, 0.35), 0.15f32.mul_add(-weather, 0.25));
 
     if after_day {
-        day_color.lerp(&night_color, progress)
+        day_color.lerp_clamped(&night_color, progress)
     } else {
-        night_color.lerp(&day_color, progress)
+        night_color.lerp_clamped(&day_color, progress)
     }
 }
 
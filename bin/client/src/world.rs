@@ -10,36 +10,46 @@ use ahash::{HashMap, HashSet};
 use mavelin_engine::{KeyCode, MouseButton, WindowContext};
 #[cfg(feature = "multiplayer")]
 use mavelin_network::{IncomingPacket, OutgoingPacket, Uuid};
-use mavelin_physics::{Aabb, PhysicsBody, PhysicsContext};
+use mavelin_physics::{Aabb, PhysicsBody, PhysicsContext, RayCastResult};
 use mavelin_shared::{Color, Face, Ranged, Rect};
-use mavelin_tween::{Animation, RepeatMode, Tween};
+use mavelin_tween::{Animation, Curve, RepeatMode, Tween};
 use mavelin_world::{
-    BfsLight, Biome, BlockSource, CHUNK_HEIGHT, Chunk, ChunkAccess, ChunkCache, ChunkManager, ChunkStage, LightNode, LocalChunkManager, SUBCHUNK_COUNT,
-    SUBCHUNK_SIZE, SubChunkBlockState,
+    BfsLight, Biome, BlockSource, BlockTicks, CHUNK_HEIGHT, Chunk, ChunkAccess, ChunkCache, ChunkManager, ChunkStage, LightNode, LocalChunkManager,
+    SUBCHUNK_COUNT, SUBCHUNK_SIZE, SubChunkBlockState,
 };
 use mavelin_worldgen::ChunkGenerator;
 use tracing::info;
 
 use crate::{
     Camera, Interval, Item, PHYSICS_RATE, Player, ResourceStorage, TICK_RATE,
+    audio::Audio,
     clock::Clock,
     input::Input,
     physics::{AabbProvider, LimitedAabbProvider},
     player::ItemType,
     render::{
         RenderInfo,
-        chunk::{ChunkRenderer, TranslucentSubchunk, VoxelFace, VoxelMeshBuilder},
+        chunk::{ChunkRenderer, VoxelFace, VoxelMeshBuilder},
         common::CommonRenderer,
+        minimap::Minimap,
     },
-    settings::{Debugging, GraphicsSettings, Settings},
+    settings::{CrosshairStyle, Debugging, GraphicsSettings, HudSettings, Settings},
 };
 
 pub const GRASS_COLOR: Color = Color::from_hsl(120.0, 0.525, 0.525);
 
 #[non_exhaustive]
 pub enum EntityData {
-    Item { item: Item, transition: Tween<f32> },
-    Model { id: usize, rotations: Vec<(glam::Vec3, glam::Vec3)> },
+    Item {
+        item: Item,
+        transition: Tween<f32>,
+        /// Seconds left until the item can be picked up.
+        pickup_delay: f32,
+    },
+    Model {
+        id: usize,
+        rotations: Vec<(glam::Vec3, glam::Vec3)>,
+    },
 }
 
 pub struct Entity {
@@ -48,16 +58,43 @@ pub struct Entity {
 }
 
 impl Entity {
+    /// Seconds after spawning during which a dropped item can't be picked up,
+    /// so a just-broken block isn't collected instantly.
+    pub const PICKUP_DELAY: f32 = 0.5;
+    /// Distance around the player's AABB within which dropped items are
+    /// picked up.
+    pub const PICKUP_RADIUS: f64 = 0.5;
+
     pub fn item(position: glam::Vec3, item: Item) -> Self {
         Self {
             body: PhysicsBody::new(position, glam::Vec3::new(0.3, 0.3, 0.3)),
             data: EntityData::Item {
                 item,
                 transition: Tween::new(0.0, 1.0, 2000).with_repeat_mode(RepeatMode::Infinite),
+                pickup_delay: Self::PICKUP_DELAY,
             },
         }
     }
 
+    /// Counts down the pickup delay of a dropped item and returns whether it
+    /// is close enough to `player_aabb` to be picked up. Always `false` for
+    /// other entities.
+    pub fn update_pickup(&mut self, player_aabb: &Aabb, delta: f32) -> bool {
+        let EntityData::Item { pickup_delay, .. } = &mut self.data else {
+            return false;
+        };
+
+        if *pickup_delay > 0.0 {
+            *pickup_delay = (*pickup_delay - delta).max(0.0);
+
+            return false;
+        }
+
+        let radius = glam::DVec3::splat(Self::PICKUP_RADIUS);
+
+        self.body.aabb().intersects(&Aabb::new(player_aabb.min - radius, player_aabb.max + radius))
+    }
+
     pub fn model(position: glam::Vec3, id: usize, resource_storage: &ResourceStorage) -> Self {
         Self {
             body: PhysicsBody::new(position, resource_storage.entity_models.get_aabb(id as u8).unwrap().size().as_vec3()),
@@ -87,7 +124,7 @@ impl Entity {
 
     pub fn render_to<C: ChunkCache>(&self, builder: &mut VoxelMeshBuilder, chunk_manager: &ChunkManager<C>, resource_storage: &ResourceStorage) {
         match &self.data {
-            EntityData::Item { transition, item } => {
+            EntityData::Item { transition, item, .. } => {
                 let animation_value = transition.get_copy();
                 let model = resource_storage.models.get_unchecked(resource_storage.blocks.get_model_by_name(item.id));
                 let mut current_block = self.body.position.floor().as_ivec3();
@@ -96,7 +133,10 @@ impl Entity {
                 let animation_value = if animation_value > 0.5 { 1.0 - animation_value } else { animation_value };
                 let position_offset = glam::Vec3::new(0.0, const { glam::Vec3::new(0.3, 0.3, 0.3).y / 2.0 }, 0.0);
                 let block_below = loop {
-                    if chunk_manager.get_block(current_block).is_some_and(|b| !b.is_air()) {
+                    if chunk_manager
+                        .get_block(current_block)
+                        .is_some_and(|b| resource_storage.blocks.get_unchecked(b.id).is_solid())
+                    {
                         break Some(current_block);
                     } else if current_block.y <= 0 {
                         break None;
@@ -303,6 +343,9 @@ pub enum Weather {
     Thunder,
 }
 
+/// Opaque and translucent faces of a single subchunk.
+type SubchunkMesh = [Vec<VoxelFace>; 2];
+
 #[allow(clippy::large_enum_variant)]
 enum JobResult {
     /// Bare terrain
@@ -321,7 +364,7 @@ enum JobResult {
     },
     Meshing {
         origin: glam::IVec2,
-        mesh: Box<[[Vec<VoxelFace>; 2]]>,
+        mesh: Box<[(usize, SubchunkMesh)]>,
     },
 }
 
@@ -424,26 +467,20 @@ impl JobManager {
         });
     }
 
-    fn spawn_meshing_job(&self, origin: glam::IVec2, chunk_manager: LocalChunkManager, resource_storage: Arc<ResourceStorage>, settings: GraphicsSettings) {
+    fn spawn_meshing_job(
+        &self,
+        origin: glam::IVec2,
+        subchunks: u16,
+        chunk_manager: LocalChunkManager,
+        resource_storage: Arc<ResourceStorage>,
+        settings: GraphicsSettings,
+    ) {
         let sender = self.sender.clone();
 
         rayon::spawn(move || {
             let instant = Instant::now();
             let snapshot = WorldSnapshot::new(&chunk_manager, resource_storage, settings);
-            let mesh = (0..SUBCHUNK_COUNT)
-                .rev()
-                .map(|subchunk_idx| {
-                    if snapshot.chunk_manager.get_chunk(origin).unwrap().subchunks[subchunk_idx]
-                        .palette
-                        .iter()
-                        .any(|block| !block.is_air())
-                    {
-                        snapshot.compute_subchunk_mesh(origin, subchunk_idx)
-                    } else {
-                        [Vec::new(), Vec::new()]
-                    }
-                })
-                .collect();
+            let mesh = snapshot.compute_dirty_subchunk_meshes(origin, subchunks);
 
             _ = sender.send(JobResult::Meshing { origin, mesh });
 
@@ -452,6 +489,24 @@ impl JobManager {
     }
 }
 
+/// Block the player is currently breaking.
+#[derive(Debug, Clone, Copy)]
+pub struct Mining {
+    pub position: glam::IVec3,
+    pub face: Face,
+    /// Breaking progress, the block is destroyed once it reaches `1.0`.
+    pub progress: f32,
+}
+
+impl Mining {
+    pub const STAGES: u8 = 10;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn stage(&self) -> u8 {
+        ((self.progress * f32::from(Self::STAGES)) as u8).min(Self::STAGES - 1)
+    }
+}
+
 pub struct WorldColors {
     pub biome: Biome,
     pub sky: Tween<Color>,
@@ -460,6 +515,7 @@ pub struct WorldColors {
 
 pub struct World {
     pub clock: Clock,
+    pub block_ticks: BlockTicks,
     pub tick_interval: Interval,
     pub physics_interval: Interval,
 
@@ -490,9 +546,24 @@ pub struct World {
     pub marked: Option<glam::IVec3>,
 
     pub colors: WorldColors,
+    /// Base field of view, widened while the player is sprinting.
+    pub fov: Tween<f32>,
+    pub minimap: Minimap,
+    pub mining: Option<Mining>,
+    /// Time left before holding the button starts breaking the next block.
+    mining_cooldown: Duration,
 }
 
 impl World {
+    /// Pause after a block breaks while the button is held, so blocks that
+    /// break instantly don't go one per frame.
+    const BREAK_COOLDOWN: Duration = Duration::from_millis(250);
+    /// Seconds it takes to break a block with a hardness of `1.0`.
+    const BREAK_TIME: f32 = 0.75;
+    /// Fraction of the render distance at which chunks start fading into the
+    /// fog.
+    const FOG_START: f32 = 0.75;
+
     pub fn new(
         context: &WindowContext,
         texture: &wgpu::Texture,
@@ -514,6 +585,8 @@ impl World {
             fog: Tween::new(fog, fog, 1000),
         };
 
+        let fov = Tween::new(Camera::DEFAULT_FOV, Camera::DEFAULT_FOV, 200).with_curve(Curve::EASE_OUT);
+
         let query_set = context.device.create_query_set(&wgpu::QuerySetDescriptor {
             label: Some("World Timestamp Query Set"),
             count: 2,
@@ -546,8 +619,13 @@ impl World {
             player,
             inventory_slot: Ranged::new(0, 0, 8),
             clock: Clock::default(),
+            block_ticks: BlockTicks::new(0),
             chunk_manager,
             colors,
+            fov,
+            minimap: Minimap::default(),
+            mining: None,
+            mining_cooldown: Duration::ZERO,
             job_manager: JobManager::new(),
             resource_storage: resource_storage.clone(),
             chat_history: Vec::new(),
@@ -601,17 +679,129 @@ impl World {
     pub fn destroy_looking_at(&mut self) {
         if let Some(looking_at) = self.camera.looking_at {
             self.destroy_block2(looking_at.position);
+            self.update_looking_at();
+        }
+    }
+
+    /// Returns the block the camera looks at within `reach` blocks, e.g. to
+    /// probe further than [`Camera::reach`] without changing it.
+    pub fn raycast_from_camera(&self, reach: f32) -> Option<RayCastResult> {
+        Self::camera_raycast(&self.camera, &self.chunk_manager, &self.entities, &self.resource_storage, reach)
+    }
+
+    /// Picks the block the camera looks at again, call it whenever blocks
+    /// change without the camera moving.
+    pub fn update_looking_at(&mut self) {
+        self.camera.looking_at = self.raycast_from_camera(self.camera.reach);
+    }
+
+    fn camera_raycast<C: ChunkCache>(
+        camera: &Camera,
+        chunk_manager: &ChunkManager<C>,
+        entities: &EntityManager,
+        storage: &ResourceStorage,
+        reach: f32,
+    ) -> Option<RayCastResult> {
+        camera.raycast(
+            &PhysicsContext::new(AabbProvider {
+                chunk_manager,
+                entity_manager: entities,
+                storage,
+            }),
+            reach,
+        )
+    }
+
+    /// Advances breaking of the block the camera is looking at, starting over
+    /// whenever the target changes. Returns the position and id of the block
+    /// once it breaks, after which nothing is mined for
+    /// [`Self::BREAK_COOLDOWN`].
+    pub fn mine_looking_at(&mut self, delta: Duration) -> Option<(glam::IVec3, u32)> {
+        if !self.mining_cooldown.is_zero() {
+            self.mining_cooldown = self.mining_cooldown.saturating_sub(delta);
+
+            return None;
+        }
+
+        let Some(looking_at) = self.camera.looking_at else {
+            self.mining = None;
+
+            return None;
+        };
+
+        let id = self.chunk_manager.get_block(looking_at.position).map_or(0, |state| state.id);
+        // Unknown blocks take as long as the default hardness instead of
+        // breaking instantly.
+        let hardness = self.resource_storage.blocks.get(id).map_or(1.0, |block| block.hardness());
+
+        let mut mining = match self.mining {
+            Some(mining) if mining.position == looking_at.position => mining,
+            _ => Mining {
+                position: looking_at.position,
+                face: looking_at.hit_side,
+                progress: 0.0,
+            },
+        };
+
+        mining.face = looking_at.hit_side;
+        mining.progress += if hardness > 0.0 {
+            delta.as_secs_f32() / (hardness * Self::BREAK_TIME)
+        } else {
+            1.0
+        };
 
-            let provider = AabbProvider {
-                chunk_manager: &self.chunk_manager,
-                entity_manager: &self.entities,
-                storage: self.resource_storage.as_ref(),
-            };
+        if mining.progress >= 1.0 {
+            self.mining = None;
+            self.mining_cooldown = Self::BREAK_COOLDOWN;
+            self.destroy_looking_at();
+
+            Some((looking_at.position, id))
+        } else {
+            self.mining = Some(mining);
+
+            None
+        }
+    }
+
+    /// Plays the `action` sound (`break` or `place`) of the block's sound
+    /// group at its center.
+    fn play_block_sound(&self, audio: &mut Audio, position: glam::IVec3, id: u32, action: &str) {
+        if let Some(name) = self.resource_storage.blocks.get(id).and_then(|block| block.sound_group().name())
+            && let Some(sound) = audio.get(&format!("{name}_{action}"))
+        {
+            audio.play_spatial(sound, position.as_vec3() + 0.5, &self.camera);
+        }
+    }
+
+    /// Reloads changed block models and textures (see
+    /// [`ResourceStorage::reload`]) and remeshes every chunk if a model
+    /// changed. `shared` has to be the storage the world was created with.
+    ///
+    /// Mutating the storage requires every handle to it, so nothing is
+    /// reloaded and [`None`] is returned while a background job holds one.
+    pub fn reload_resources(mut self, shared: &mut Arc<ResourceStorage>) -> (Self, Option<(Vec<usize>, bool)>) {
+        drop(self.resource_storage);
+        drop(self.entities.resource_storage);
+
+        let reloaded = Arc::get_mut(shared).map(ResourceStorage::reload);
 
-            let context = PhysicsContext::new(provider);
+        self.resource_storage = shared.clone();
+        self.entities.resource_storage = shared.clone();
 
-            self.camera.update_looking_at(&context);
+        if reloaded.as_ref().is_some_and(|(models, _)| !models.is_empty()) {
+            for chunk in self.chunk_manager.chunks_mut() {
+                chunk.mark_all_dirty();
+            }
+        }
+
+        if reloaded
+            .as_ref()
+            .is_some_and(|(models, textures_changed)| *textures_changed || !models.is_empty())
+        {
+            self.minimap.invalidate();
         }
+
+        (self, reloaded)
     }
 
     pub fn place(&mut self, position: glam::IVec3, id: u32) {
@@ -624,39 +814,11 @@ impl World {
 
             if let Some(chunk) = self.chunk_manager.get_chunk_mut(chunk) {
                 chunk.set_block(local, SubChunkBlockState::new(id));
-                chunk.dirty = true;
 
                 info!("placed block at {position}");
             }
 
-            for normal in Face::NORMALS {
-                let chunk_position = ChunkManager::<()>::to_local(position + normal);
-
-                if chunk_position != chunk
-                    && let Some(chunk) = self.chunk_manager.get_chunk_mut(chunk_position)
-                {
-                    chunk.dirty = true;
-
-                    info!("marked neighbour {chunk_position} dirty");
-                }
-            }
-
-            for normal in [
-                glam::IVec3::NEG_ONE,
-                glam::IVec3::NEG_ONE.with_x(1),
-                glam::IVec3::ONE.with_x(-1),
-                glam::IVec3::ONE,
-            ] {
-                let chunk_position = ChunkManager::<()>::to_local(position + normal);
-
-                if chunk_position != chunk
-                    && let Some(chunk) = self.chunk_manager.get_chunk_mut(chunk_position)
-                {
-                    chunk.dirty = true;
-
-                    info!("marked neighbour {chunk_position} dirty");
-                }
-            }
+            self.chunk_manager.mark_neighbours_dirty(position);
 
             info!("calculating light");
 
@@ -680,27 +842,28 @@ impl World {
         }
     }
 
-    pub fn place_held(&mut self) {
-        let Some(result) = self.camera.looking_at else {
-            return;
-        };
+    /// Whether a block can be placed at `position`: the cell has to be loaded
+    /// and empty, and the placed block must not intersect the player.
+    pub fn can_place_at(&self, position: glam::IVec3) -> bool {
+        self.chunk_manager.get_block(position).is_some_and(|block| block.is_air()) && !Aabb::cube(position.as_dvec3()).intersects(&self.player.aabb())
+    }
 
+    /// Places the selected hotbar block against the face the camera is
+    /// looking at. Returns the position and id of the placed block.
+    pub fn place_held(&mut self) -> Option<(glam::IVec3, u32)> {
+        let result = self.camera.looking_at?;
         let position = result.position + result.hit_side.as_normal();
 
-        if self.chunk_manager.get_block(position).is_none_or(|block| !block.is_air()) || Aabb::cube(position.as_dvec3()).intersects(&self.player.aabb()) {
-            return;
+        if !self.can_place_at(position) {
+            return None;
         }
 
-        let Some((id, _)) = self.player.inventory.take_hotbar_item(self.inventory_slot.value as usize) else {
-            return;
-        };
+        let (id, _) = self.player.inventory.take_hotbar_item(self.inventory_slot.value as usize)?;
 
         self.place(position, id);
-        self.camera.update_looking_at(&PhysicsContext::new(AabbProvider {
-            chunk_manager: &self.chunk_manager,
-            entity_manager: &self.entities,
-            storage: self.resource_storage.as_ref(),
-        }));
+        self.update_looking_at();
+
+        Some((position, id))
     }
 
     #[profiling::function]
@@ -714,9 +877,14 @@ impl World {
         let context = PhysicsContext::new(provider);
         let grounded = self.player.body.is_on_ground;
 
-        context.physics_step(&mut self.player.body, PHYSICS_RATE.as_secs_f32());
+        if self.player.movement_mode.has_collision() {
+            context.physics_step(&mut self.player.body, PHYSICS_RATE.as_secs_f32());
+        } else {
+            self.player.body.position += self.player.body.velocity * PHYSICS_RATE.as_secs_f32();
+            self.player.body.is_on_ground = false;
+        }
 
-        self.player.physics_step(input, &mut self.camera, PHYSICS_RATE.as_secs_f32());
+        self.player.physics_step(input, PHYSICS_RATE.as_secs_f32());
         self.player.body.config.friction = if self.player.body.config.gravity_scale <= 1e-7 {
             8.0
         } else if !grounded {
@@ -725,7 +893,7 @@ impl World {
             10.0
         };
 
-        self.camera.set_position(&context, self.player.camera_position());
+        self.camera.follow(&context, self.player.camera_position(), PHYSICS_RATE.as_secs_f32());
 
         let player_aabb = self.player.aabb();
         let mut remove_entities: Vec<usize> = Vec::new();
@@ -738,12 +906,8 @@ impl World {
         for (id, entity) in &mut self.entities {
             context.physics_step(&mut entity.body, PHYSICS_RATE.as_secs_f32());
 
-            if matches!(entity.data, EntityData::Item { .. }) {
-                let entity_aabb = entity.body.aabb();
-
-                if entity_aabb.intersects(&player_aabb) {
-                    remove_entities.push(*id);
-                }
+            if entity.update_pickup(&player_aabb, PHYSICS_RATE.as_secs_f32()) {
+                remove_entities.push(*id);
             }
         }
 
@@ -756,10 +920,40 @@ impl World {
         }
     }
 
+    /// Schedules a block tick at `position` to fire after `delay_ticks` game
+    /// ticks, see [`mavelin_storage::Block::on_tick`].
+    pub fn schedule_block_tick(&mut self, position: glam::IVec3, delay_ticks: u64) {
+        self.block_ticks.schedule(position, delay_ticks);
+    }
+
+    /// Fires the block ticks that are due and a few random ticks in every
+    /// loaded chunk.
+    fn tick_blocks(&mut self) {
+        let mut positions = self.block_ticks.advance();
+
+        for chunk in self.chunk_manager.chunks() {
+            positions.extend(self.block_ticks.random_positions(chunk.origin));
+        }
+
+        for position in positions {
+            if let Some(state) = self.chunk_manager.get_block(position)
+                && !state.is_air()
+            {
+                let block = self.resource_storage.blocks.get_unchecked(state.id);
+
+                block.on_tick(&mut self.chunk_manager, position);
+            }
+        }
+    }
+
     #[profiling::function]
     pub fn tick(&mut self) {
         self.clock.tick();
 
+        if matches!(self.ty, WorldType::Local) {
+            self.tick_blocks();
+        }
+
         #[cfg(feature = "multiplayer")]
         if let WorldType::Remote { sender, receiver, player_uuid } = &mut self.ty {
             if let Some(uuid) = *player_uuid {
@@ -800,9 +994,10 @@ impl World {
 
     #[allow(clippy::too_many_lines)]
     #[profiling::function]
-    pub fn update(&mut self, context: &WindowContext, settings: GraphicsSettings, input: &Input, delta: Duration) {
+    pub fn update(&mut self, context: &WindowContext, settings: GraphicsSettings, input: &Input, audio: &mut Audio, delta: Duration) {
         self.colors.sky.advance(delta);
         self.colors.fog.advance(delta);
+        self.fov.advance(delta);
 
         self.player.handle_keyboard(input);
 
@@ -811,21 +1006,49 @@ impl World {
         }
 
         if self.clock.active() {
+            let sprinting = self.player.sprinting;
+
             for _ in 0..self.physics_interval.update(delta) {
                 self.physics_step(input);
             }
 
+            if self.player.sprinting != sprinting {
+                self.fov.set(if self.player.sprinting {
+                    Camera::DEFAULT_FOV * Player::SPRINT_FOV_SCALE
+                } else {
+                    Camera::DEFAULT_FOV
+                });
+            }
+
+            let fall_scale = (self.player.body.velocity.y.abs() / 8.0).clamp(1.0, 1.75);
+
+            self.camera.set_fov(self.fov.get_copy() * fall_scale);
+
             for _ in 0..self.tick_interval.update(delta) {
                 self.tick();
             }
         }
 
-        if input.mouse.is_pressed_once(MouseButton::Left) {
-            self.destroy_looking_at();
-        } else if input.mouse.is_pressed(MouseButton::Right) {
-            self.place_held();
+        if input.mouse.is_pressed(MouseButton::Left) {
+            if let Some((position, id)) = self.mine_looking_at(delta) {
+                self.play_block_sound(audio, position, id, "break");
+            }
+        } else {
+            self.mining = None;
+            self.mining_cooldown = Duration::ZERO;
+
+            if input.mouse.is_pressed_once(MouseButton::Right)
+                && let Some((position, id)) = self.place_held()
+            {
+                self.play_block_sound(audio, position, id, "place");
+            }
         }
 
+        let player_column = self.player.body.position.floor().as_ivec3();
+
+        self.minimap.set_center(glam::IVec2::new(player_column.x, player_column.z));
+        self.minimap.update(&self.chunk_manager, &self.resource_storage);
+
         if let Some(biome) = self.chunk_manager.get_biome(self.player.body.position.as_ivec3())
             && self.colors.biome != biome
         {
@@ -876,7 +1099,7 @@ impl World {
                         if self.chunk_manager.stages.get(&origin).is_some_and(|stage| stage >= &ChunkStage::Meshed) {
                             let chunk = unsafe { self.chunk_manager.get_chunk_mut(origin).unwrap_unchecked() };
 
-                            chunk.dirty = true;
+                            chunk.mark_all_dirty();
                         }
                     }
                 }
@@ -887,12 +1110,11 @@ impl World {
 
                     self.chunk_manager.set_stage(origin, ChunkStage::Meshed);
 
-                    for (subchunk_idx, mesh) in mesh.into_iter().rev().enumerate() {
-                        let (solid, translucent) = mesh.into();
-                        let solid = VoxelMeshBuilder::build_from_slice(context.device, &solid, "Solid SubChunk: Vertices", "Solid SubChunk: Indices");
-                        let translucent = TranslucentSubchunk::new(context.device, translucent, self.player.camera_position(), origin);
+                    for (subchunk_idx, mesh) in mesh {
+                        let [solid, translucent] = mesh;
 
-                        self.chunk_renderer.set_subchunk((origin, subchunk_idx), solid, translucent);
+                        self.chunk_renderer
+                            .update_subchunk(context, (origin, subchunk_idx), &solid, translucent, self.player.camera_position());
                     }
                 }
             }
@@ -972,21 +1194,22 @@ impl World {
 
                         self.job_manager.jobs.insert(origin);
                         self.job_manager
-                            .spawn_meshing_job(origin, chunk_manager, self.resource_storage.clone(), settings);
+                            .spawn_meshing_job(origin, Chunk::ALL_SUBCHUNKS, chunk_manager, self.resource_storage.clone(), settings);
 
                         queue.push((origin, ChunkStage::MeshingInProgress));
                     }
                     Some(ChunkStage::Meshed)
                         if settings.render_shape.test(player_origin, origin)
                             && self.chunk_manager.neighbours_at_least(origin, ChunkStage::Lighted)
-                            && self.chunk_manager.get_chunk(origin).is_some_and(|chunk| chunk.dirty)
+                            && self.chunk_manager.get_chunk(origin).is_some_and(|chunk| chunk.is_dirty())
                             && !self.job_manager.jobs.contains(&origin) =>
                     {
                         let chunk_manager = self.chunk_manager.local_of(origin).unwrap();
+                        let subchunks = self.chunk_manager[origin].dirty_subchunks;
 
                         self.job_manager.jobs.insert(origin);
                         self.job_manager
-                            .spawn_meshing_job(origin, chunk_manager, self.resource_storage.clone(), settings);
+                            .spawn_meshing_job(origin, subchunks, chunk_manager, self.resource_storage.clone(), settings);
 
                         queue.push((origin, ChunkStage::MeshingInProgress));
                     }
@@ -1000,7 +1223,7 @@ impl World {
                 if matches!(stage, ChunkStage::MeshingInProgress)
                     && let Some(chunk) = self.chunk_manager.get_chunk_mut(origin)
                 {
-                    chunk.dirty = false;
+                    chunk.take_dirty_subchunks();
                 }
             }
         });
@@ -1061,10 +1284,12 @@ impl World {
 
             let pass = &mut pass;
 
-            self.chunk_renderer.set_fog_color(context.queue, fog_color);
+            let fog_end = f32::from(settings.graphics.render_shape.radius()) * SUBCHUNK_SIZE as f32;
+
+            self.chunk_renderer
+                .set_fog(context.queue, fog_end * Self::FOG_START, fog_end, fog_color);
 
-            let rendered_subchunks = self
-                .chunk_renderer
+            self.chunk_renderer
                 .render(context.device, pass, self.camera.position, &self.camera.frustum, self.camera.matrix());
 
             /*let mut builder = VoxelMeshBuilder::with_capacity(self.entities.len());
@@ -1091,15 +1316,27 @@ impl World {
                 common_renderer.draw_rect(glam::Vec2::ZERO, surface_size.as_vec2(), Color::from_hsl(215.0, 1.0, 0.6).with_alpha(0.5));
             }
 
+            self.render_selection_outline(common_renderer, surface_size);
+            self.render_mining_overlay(common_renderer, surface_size);
             self.render_hotbar(context, common_renderer, surface_size);
+            self.render_crosshair(common_renderer, settings.hud, surface_size);
 
             if settings.debugging.enabled {
-                self.render_debug_text(common_renderer, context, settings.graphics, rendered_subchunks.draw_calls, surface_size);
+                self.render_debug_text(common_renderer, context, settings.graphics, surface_size);
                 self.render_chunk_map(context.queue, common_renderer, surface_size);
 
-                Self::render_fps_stat(context.queue, common_renderer, &settings.debugging, delta, surface_size);
+                Self::render_fps_stat(context.queue, common_renderer, &settings.debugging, context.smoothed_delta, surface_size);
                 Self::render_draw_calls_stat(context.queue, common_renderer, &settings.debugging, info, surface_size);
             } else {
+                const MINIMAP_SIZE: glam::Vec2 = glam::Vec2::splat(128.0);
+
+                self.minimap.draw(
+                    context.queue,
+                    common_renderer,
+                    glam::Vec2::new(surface_size.as_vec2().x - MINIMAP_SIZE.x - 12.0, 12.0),
+                    MINIMAP_SIZE,
+                );
+
                 common_renderer.draw_text(
                     context.queue,
                     glam::Vec2::new(8.0, 4.0),
@@ -1282,7 +1519,6 @@ impl World {
         context: &mut CommonRenderer,
         backend: &WindowContext,
         GraphicsSettings { render_shape, vsync, .. }: GraphicsSettings,
-        rendered_subchunks: usize,
         glam::UVec2 { x, y }: glam::UVec2,
     ) {
         let (hours, minutes) = {
@@ -1337,7 +1573,8 @@ impl World {
             );
 
         let total_chunks = self.chunk_manager.len();
-        let total_subchunks = total_chunks * SUBCHUNK_COUNT;
+        let rendered_subchunks = self.chunk_renderer.rendered_subchunks();
+        let total_subchunks = self.chunk_renderer.meshed_subchunks();
 
         let text = format!(
             "Render Backend: {renderer}
@@ -1359,6 +1596,101 @@ Rendered subchunks: {rendered_subchunks} / {total_subchunks} ({total_chunks} tot
         context.draw_text(backend.queue, glam::Vec2::new(8.0, 4.0), "default", text, Color::WHITE, 18.0, None);
     }
 
+    /// Projects a point in the world onto the screen, [`None`] if it is behind
+    /// the camera.
+    fn project_to_screen(&self, point: glam::Vec3, surface_size: glam::UVec2) -> Option<glam::Vec2> {
+        let clip = self.camera.world_matrix() * point.extend(1.0);
+
+        (clip.w > 0.0).then(|| {
+            let ndc = clip.truncate() / clip.w;
+
+            glam::Vec2::new((ndc.x + 1.0) / 2.0, (1.0 - ndc.y) / 2.0) * surface_size.as_vec2()
+        })
+    }
+
+    /// Outlines every element of the block the player is looking at. Rotated
+    /// elements are traced with the same transform their faces are baked
+    /// with, so the outline matches what is rendered rather than the
+    /// unrotated cuboid. Edges hidden behind the block are skipped, since the
+    /// outline is drawn over the scene without depth testing.
+    fn render_selection_outline(&self, context: &mut CommonRenderer, surface_size: glam::UVec2) {
+        const INFLATE: f32 = 0.002;
+
+        let Some(looking_at) = self.camera.looking_at else {
+            return;
+        };
+
+        let Some(block) = self.chunk_manager.get_block(looking_at.position) else {
+            return;
+        };
+
+        let model = self
+            .resource_storage
+            .models
+            .get_unchecked(self.resource_storage.blocks.get_model_by_name(block.id));
+        let position = looking_at.position.as_vec3();
+        let eye = self.camera.position - position;
+
+        for element in &model.elements {
+            let center = (element.cube.min + element.cube.max).as_vec3() / 2.0;
+
+            for [start, end] in element.visible_edges(eye) {
+                let start = position + start + (start - center).signum() * INFLATE;
+                let end = position + end + (end - center).signum() * INFLATE;
+
+                if let (Some(start), Some(end)) = (self.project_to_screen(start, surface_size), self.project_to_screen(end, surface_size)) {
+                    context.draw_line(start, end, 2.0, Color::BLACK.with_alpha(0.6));
+                }
+            }
+        }
+    }
+
+    /// Darkens the face of the block being broken and grows cracks from its
+    /// center towards the corners as the breaking progresses.
+    fn render_mining_overlay(&self, context: &mut CommonRenderer, surface_size: glam::UVec2) {
+        let Some(mining) = self.mining else {
+            return;
+        };
+
+        let offset = mining.face.as_normal().as_vec3() * 0.001;
+        let project = |vertex: glam::Vec3| self.project_to_screen(mining.position.as_vec3() + vertex + offset, surface_size);
+
+        let [a, b, c, d] = mining.face.as_vertices();
+        let Some(corners) = [a, b, d, c].into_iter().map(project).collect::<Option<Vec<_>>>() else {
+            return;
+        };
+
+        let stage = f32::from(mining.stage() + 1) / f32::from(Mining::STAGES);
+        let center = corners.iter().sum::<glam::Vec2>() / 4.0;
+
+        context.draw_polygon(&corners, Color::BLACK.with_alpha(0.1 + stage * 0.3));
+
+        for corner in corners {
+            context.draw_line(center, center.lerp(corner, stage), 2.0, Color::BLACK.with_alpha(0.6));
+        }
+    }
+
+    fn render_crosshair(&self, context: &mut CommonRenderer, hud: HudSettings, surface_size: glam::UVec2) {
+        const THICKNESS: f32 = 2.0;
+
+        let center = (surface_size.as_vec2() / 2.0).floor();
+        let size = hud.crosshair_size;
+        let color = match hud.crosshair_target_color {
+            Some(color) if self.camera.looking_at.is_some() => color,
+            _ => hud.crosshair_color,
+        };
+
+        match hud.crosshair {
+            CrosshairStyle::Hidden => (),
+            CrosshairStyle::Plus => {
+                context.draw_rect(center - glam::Vec2::new(size, THICKNESS) / 2.0, glam::Vec2::new(size, THICKNESS), color);
+                context.draw_rect(center - glam::Vec2::new(THICKNESS, size) / 2.0, glam::Vec2::new(THICKNESS, size), color);
+            }
+            CrosshairStyle::Dot => context.draw_circle(center, size / 8.0, color),
+            CrosshairStyle::Circle => context.draw_circle_outline(center, size / 2.0, THICKNESS, color),
+        }
+    }
+
     #[profiling::function]
     fn render_hotbar(&self, backend: &WindowContext, context: &mut CommonRenderer, surface_size: glam::UVec2) {
         const INVENTORY_HOTBAR_SLOTS: u8 = 8;
@@ -1437,16 +1769,19 @@ Rendered subchunks: {rendered_subchunks} / {total_subchunks} ({total_chunks} tot
             x += ELEMENT_WIDTH + SPACING;
         }
 
-        let text = context
-            .measure("default", format!("draw calls: {}\nvertices: {}", info.draw_calls, info.vertices), 9.0, None)
-            .unwrap_or_default();
+        let (draw_calls, vertices) = (debugging.render_stats.draw_calls(), debugging.render_stats.vertices());
+        let text = format!(
+            "draw calls: {} (avg {} / peak {})\nvertices: {} (avg {} / peak {})",
+            info.draw_calls, draw_calls.avg, draw_calls.max, info.vertices, vertices.avg, vertices.max
+        );
+        let text_size = context.measure("default", &text, 9.0, None).unwrap_or_default();
 
-        context.draw_rect(container_origin + glam::Vec2::splat(4.0), text, Color::from_u32_rgb(0x1D211B));
+        context.draw_rect(container_origin + glam::Vec2::splat(4.0), text_size, Color::from_u32_rgb(0x1D211B));
         context.draw_text(
             queue,
             container_origin + glam::Vec2::splat(4.0),
             "default",
-            format!("draw calls: {}\nvertices: {}", info.draw_calls, info.vertices),
+            text,
             Color::from_hsl(110.0, 0.5, 0.8),
             9.0,
             None,
@@ -1545,6 +1880,25 @@ impl<'a, C: ChunkAccess> WorldSnapshot<'a, C> {
         }
     }
 
+    /// Meshes the subchunks selected by the `subchunks` bit mask, skipping
+    /// the ones that contain only air. Skipped subchunks still get an empty
+    /// mesh, so that whatever was drawn there before is removed.
+    pub fn compute_dirty_subchunk_meshes(&self, origin: glam::IVec2, subchunks: u16) -> Box<[(usize, SubchunkMesh)]> {
+        let chunk = self.chunk_manager.get_chunk(origin).unwrap();
+
+        (0..SUBCHUNK_COUNT)
+            .rev()
+            .filter(|subchunk_idx| subchunks & (1 << subchunk_idx) != 0)
+            .map(|subchunk_idx| {
+                if chunk.subchunks[subchunk_idx].is_empty() {
+                    (subchunk_idx, [Vec::new(), Vec::new()])
+                } else {
+                    (subchunk_idx, self.compute_subchunk_mesh(origin, subchunk_idx))
+                }
+            })
+            .collect()
+    }
+
     pub fn compute_subchunk_mesh(&self, origin: glam::IVec2, subchunk_idx: usize) -> [Vec<VoxelFace>; 2] {
         use std::cell::RefCell;
 
@@ -1589,7 +1943,7 @@ impl<'a, C: ChunkAccess> WorldSnapshot<'a, C> {
                 for element in &model.elements {
                     for model_face in &element.faces {
                         let culled = model_face.cull_face.as_ref().is_some_and(|&(cull_face_normal, _, _, opposite_face)| {
-                            neighbours[cull_face_normal].is_some_and(|(culled, model)| model.is_opaque(opposite_face) || culled)
+                            neighbours[cull_face_normal].is_some_and(|(culled, model)| model.occludes(opposite_face) || culled)
                         });
 
                         if !culled {
@@ -1636,3 +1990,67 @@ impl<'a, C: ChunkAccess> WorldSnapshot<'a, C> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use mavelin_physics::Aabb;
+    use mavelin_world::{Chunk, ChunkAccess, ChunkManager, ChunkStage, SubChunkBlockState};
+
+    use super::{Entity, EntityManager, World};
+    use crate::{
+        Camera, Item, ResourceStorage,
+        blocks::{AirBlock, StoneBlock},
+        player::ItemType,
+    };
+
+    const ITEM: Item = Item {
+        id: 1,
+        ty: ItemType::Block,
+        amount: 1,
+    };
+
+    #[test]
+    fn test_item_pickup_after_delay() {
+        const DELTA: f32 = 1.0 / 60.0;
+
+        let player_aabb = Aabb::new(glam::DVec3::new(-0.3, 0.0, -0.3), glam::DVec3::new(0.3, 1.8, 0.3));
+        let mut near = Entity::item(glam::Vec3::new(0.0, 0.5, 0.0), ITEM);
+        let mut far = Entity::item(glam::Vec3::new(5.0, 0.5, 0.0), ITEM);
+        let mut elapsed = 0.0;
+
+        while elapsed + DELTA < Entity::PICKUP_DELAY {
+            assert!(!near.update_pickup(&player_aabb, DELTA));
+
+            elapsed += DELTA;
+        }
+
+        assert!((0..3).any(|_| near.update_pickup(&player_aabb, DELTA)));
+        assert!((0..60).all(|_| !far.update_pickup(&player_aabb, DELTA)));
+    }
+
+    #[test]
+    fn test_camera_raycast_hits_block_ahead() {
+        let mut storage = ResourceStorage::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../../resources"));
+
+        storage.register_block("game", AirBlock);
+        storage.register_block("game", StoneBlock);
+
+        let storage = Arc::new(storage);
+        let entities = EntityManager::new(storage.clone());
+        let mut chunk_manager = ChunkManager::default();
+
+        chunk_manager.push(Chunk::new(glam::IVec2::ZERO), ChunkStage::Lighted);
+        chunk_manager.set_block(glam::IVec3::new(5, 64, 0), SubChunkBlockState::new(storage.blocks.get_by_name("game:stone")));
+
+        // Looks along +X from the middle of the block at x = 0, so the stone
+        // block's near face is 4.5 blocks away.
+        let camera = Camera::new(glam::Vec3::new(0.5, 64.5, 0.5));
+        let hit = World::camera_raycast(&camera, &chunk_manager, &entities, &storage, camera.reach).unwrap();
+
+        assert_eq!(hit.position, glam::IVec3::new(5, 64, 0));
+        assert!((hit.hit_vec.x - 5.0).abs() < 1e-6);
+        assert!(World::camera_raycast(&camera, &chunk_manager, &entities, &storage, 4.0).is_none());
+    }
+}
@@ -109,6 +109,8 @@ struct BlockData {
     droppable: bool,
     collidable: bool,
     selectable: bool,
+    transparent: bool,
+    hardness: f32,
 }
 
 impl mavelin_storage::Block for BlockData {
@@ -143,6 +145,14 @@ impl mavelin_storage::Block for BlockData {
     fn selectable(&self) -> bool {
         self.selectable
     }
+
+    fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    fn hardness(&self) -> f32 {
+        self.hardness
+    }
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -166,6 +176,8 @@ impl BlockData {
             .field_default::<bool>("droppable", true)
             .field_default::<bool>("collidable", true)
             .field_default::<bool>("selectable", true)
+            .field_default::<bool>("transparent", false)
+            .field_default::<f32>("hardness", 1.0)
             .finish()
     }
 }
@@ -1,7 +1,10 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, unused_crate_dependencies)]
 
+mod offscreen;
+
 use std::{
     cell::Cell,
+    collections::VecDeque,
     fs::File,
     io::BufReader,
     sync::Arc,
@@ -21,6 +24,8 @@ use winit::{
 };
 pub use winit::{event::MouseButton, keyboard::KeyCode, window::CursorGrabMode};
 
+pub use self::offscreen::{OffscreenError, OffscreenTarget, read_texture_to_image};
+
 #[derive(Debug)]
 pub struct Texture {
     pub texture: wgpu::Texture,
@@ -69,6 +74,32 @@ impl Texture {
         }
     }
 }
+/// Rolling average of the last [`FrameTimer::SAMPLES`] frame times.
+#[derive(Debug, Default, Clone)]
+pub struct FrameTimer {
+    samples: VecDeque<Duration>,
+    total: Duration,
+}
+
+impl FrameTimer {
+    pub const SAMPLES: usize = 60;
+
+    pub fn push(&mut self, delta: Duration) {
+        if self.samples.len() >= Self::SAMPLES
+            && let Some(oldest) = self.samples.pop_front()
+        {
+            self.total -= oldest;
+        }
+
+        self.samples.push_back(delta);
+        self.total += delta;
+    }
+
+    pub fn smoothed(&self) -> Duration {
+        self.total.checked_div(self.samples.len() as u32).unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct WindowContext<'a> {
     pub instance: &'a wgpu::Instance,
@@ -77,9 +108,13 @@ pub struct WindowContext<'a> {
     pub depth_texture: &'a Texture,
     pub surface_format: &'a wgpu::TextureFormat,
     pub adapter: &'a wgpu::Adapter,
+    /// Frame time averaged over recent frames, steadier than the raw delta
+    /// for display purposes.
+    pub smoothed_delta: Duration,
     event_loop: &'a dyn ActiveEventLoop,
     window: &'a dyn Window,
     vsync: &'a Cell<bool>,
+    max_fps: &'a Cell<Option<u32>>,
 }
 
 impl WindowContext<'_> {
@@ -96,6 +131,17 @@ impl WindowContext<'_> {
         self.vsync.set(enabled);
     }
 
+    pub fn max_fps(&self) -> Option<u32> {
+        self.max_fps.get()
+    }
+
+    /// Limits how often frames are drawn, [`None`] redraws as fast as
+    /// possible. The event loop waits out the rest of each frame instead of
+    /// spinning.
+    pub fn set_max_fps(&self, max_fps: Option<u32>) {
+        self.max_fps.set(max_fps.filter(|&max_fps| max_fps > 0));
+    }
+
     pub fn toggle_fullscreen(&self) {
         if self.window.fullscreen().is_some() {
             self.window.set_fullscreen(None);
@@ -123,6 +169,29 @@ impl WindowContext<'_> {
     }
 }
 
+fn device_descriptor() -> wgpu::DeviceDescriptor<'static> {
+    wgpu::DeviceDescriptor {
+        required_limits: wgpu::Limits {
+            max_compute_workgroup_size_x: 0,
+            max_compute_workgroup_size_y: 0,
+            max_compute_workgroup_size_z: 0,
+            max_compute_workgroups_per_dimension: 0,
+            max_compute_invocations_per_workgroup: 0,
+            max_compute_workgroup_storage_size: 0,
+            max_storage_buffer_binding_size: 0,
+            max_storage_buffers_per_shader_stage: 0,
+            max_storage_textures_per_shader_stage: 0,
+            max_dynamic_storage_buffers_per_pipeline_layout: 0,
+            max_texture_dimension_1d: 8192,
+            max_texture_dimension_2d: 4096,
+            max_immediate_size: 96,
+            ..wgpu::Limits::downlevel_defaults()
+        },
+        required_features: wgpu::Features::IMMEDIATES | wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::MAPPABLE_PRIMARY_BUFFERS,
+        ..wgpu::DeviceDescriptor::default()
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct KeyboardModifiers {
@@ -161,9 +230,15 @@ pub struct ApplicationWindow<T: State> {
     adapter: wgpu::Adapter,
     surface: wgpu::Surface<'static>,
     surface_format: wgpu::TextureFormat,
+    /// Only asks for [`wgpu::TextureUsages::COPY_SRC`] when the surface
+    /// supports it, screenshots are unavailable otherwise.
+    surface_usage: wgpu::TextureUsages,
     depth_texture: Texture,
     last_time: Option<Instant>,
+    frame_timer: FrameTimer,
+    next_frame: Option<Instant>,
     vsync: bool,
+    max_fps: Option<u32>,
 }
 
 pub struct Application<T: State> {
@@ -229,33 +304,15 @@ impl<T: State> ApplicationWindow<T> {
 
         let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).unwrap();
 
-        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
-            required_limits: wgpu::Limits {
-                max_compute_workgroup_size_x: 0,
-                max_compute_workgroup_size_y: 0,
-                max_compute_workgroup_size_z: 0,
-                max_compute_workgroups_per_dimension: 0,
-                max_compute_invocations_per_workgroup: 0,
-                max_compute_workgroup_storage_size: 0,
-                max_storage_buffer_binding_size: 0,
-                max_storage_buffers_per_shader_stage: 0,
-                max_storage_textures_per_shader_stage: 0,
-                max_dynamic_storage_buffers_per_pipeline_layout: 0,
-                max_texture_dimension_1d: 8192,
-                max_texture_dimension_2d: 4096,
-                max_immediate_size: 96,
-                ..wgpu::Limits::downlevel_defaults()
-            },
-            required_features: wgpu::Features::IMMEDIATES | wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::MAPPABLE_PRIMARY_BUFFERS,
-            ..wgpu::DeviceDescriptor::default()
-        }))
-        .unwrap();
+        let (device, queue) = pollster::block_on(adapter.request_device(&device_descriptor())).unwrap();
 
         let surface = instance.create_surface(window.clone()).unwrap();
         let cap = surface.get_capabilities(&adapter);
         let format = cap.formats[0];
+        let surface_usage = wgpu::TextureUsages::RENDER_ATTACHMENT | (cap.usages & wgpu::TextureUsages::COPY_SRC);
 
         let vsync = Cell::new(false);
+        let max_fps = Cell::new(None);
         let depth_texture = Texture::create_depth_texture(&device, width, height, "Mavelin Depth Texture");
         let state = T::new(
             WindowContext {
@@ -263,9 +320,11 @@ impl<T: State> ApplicationWindow<T> {
                 device: &device,
                 queue: &queue,
                 surface_format: &format,
+                smoothed_delta: Duration::ZERO,
                 event_loop,
                 window: window.as_ref(),
                 vsync: &vsync,
+                max_fps: &max_fps,
                 depth_texture: &depth_texture,
                 adapter: &adapter,
             },
@@ -276,12 +335,16 @@ impl<T: State> ApplicationWindow<T> {
             state,
             window,
             last_time: None,
+            frame_timer: FrameTimer::default(),
+            next_frame: None,
             vsync: vsync.get(),
+            max_fps: max_fps.get(),
             instance,
             device,
             queue,
             surface,
             surface_format: format,
+            surface_usage,
             depth_texture,
             adapter,
         };
@@ -294,7 +357,7 @@ impl<T: State> ApplicationWindow<T> {
     fn configure_surface(&mut self, width: u32, height: u32) {
         self.depth_texture = Texture::create_depth_texture(&self.device, width, height, "Mavelin Depth Texture");
         self.surface.configure(&self.device, &wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: self.surface_usage,
             format: self.surface_format,
             color_space: wgpu::SurfaceColorSpace::Auto,
             view_formats: vec![self.surface_format.add_srgb_suffix()],
@@ -323,6 +386,7 @@ impl<T: State> ApplicationHandler for Application<T> {
         match event {
             WindowEvent::SurfaceResized(physical_size) => self.window.inspect_mut(move |window| {
                 let vsync = Cell::new(window.vsync);
+                let max_fps = Cell::new(window.max_fps);
 
                 window.configure_surface(physical_size.width, physical_size.height);
                 window.state.handle_window_resize(
@@ -331,15 +395,19 @@ impl<T: State> ApplicationHandler for Application<T> {
                         device: &window.device,
                         queue: &window.queue,
                         surface_format: &window.surface_format,
+                        smoothed_delta: window.frame_timer.smoothed(),
                         event_loop,
                         window: window.window.as_ref(),
                         vsync: &vsync,
+                        max_fps: &max_fps,
                         depth_texture: &window.depth_texture,
                         adapter: &window.adapter,
                     },
                     glam::UVec2::new(physical_size.width, physical_size.height),
                     window.window.scale_factor(),
                 );
+
+                window.max_fps = max_fps.get();
             }),
             WindowEvent::ModifiersChanged(modifiers) => {
                 let state = modifiers.state();
@@ -401,16 +469,20 @@ impl<T: State> ApplicationHandler for Application<T> {
                     .min(Duration::from_millis(150));
 
                 window.last_time.replace(now);
+                window.frame_timer.push(delta);
 
                 let vsync = Cell::new(window.vsync);
+                let max_fps = Cell::new(window.max_fps);
                 let context = WindowContext {
                     instance: &window.instance,
                     device: &window.device,
                     queue: &window.queue,
                     surface_format: &window.surface_format,
+                    smoothed_delta: window.frame_timer.smoothed(),
                     event_loop,
                     window: window.window.as_ref(),
                     vsync: &vsync,
+                    max_fps: &max_fps,
                     depth_texture: &window.depth_texture,
                     adapter: &window.adapter,
                 };
@@ -451,13 +523,36 @@ impl<T: State> ApplicationHandler for Application<T> {
                     window.configure_surface(width, height);
                 }
 
-                window.window.request_redraw();
+                window.max_fps = max_fps.get();
+
+                if let Some(max_fps) = window.max_fps {
+                    window.next_frame = Some(now + Duration::from_secs(1) / max_fps);
+                } else {
+                    window.next_frame = None;
+                    window.window.request_redraw();
+                }
             }),
             WindowEvent::CloseRequested => event_loop.exit(),
             _ => {}
         }
     }
 
+    fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
+        let Some(window) = &mut self.window else {
+            return;
+        };
+
+        match window.next_frame {
+            Some(next_frame) if Instant::now() < next_frame => event_loop.set_control_flow(ControlFlow::WaitUntil(next_frame)),
+            Some(_) => {
+                window.next_frame = None;
+                event_loop.set_control_flow(ControlFlow::Poll);
+                window.window.request_redraw();
+            }
+            None => event_loop.set_control_flow(ControlFlow::Poll),
+        }
+    }
+
     fn device_event(&mut self, _: &dyn ActiveEventLoop, _: Option<DeviceId>, event: DeviceEvent) {
         if let DeviceEvent::PointerMotion { delta } = event {
             self.window.inspect_mut(|window| {
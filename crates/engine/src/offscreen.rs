@@ -0,0 +1,177 @@
+use core::fmt;
+
+use image::RgbaImage;
+
+use crate::Texture;
+
+#[derive(Debug)]
+pub enum OffscreenError {
+    Adapter(wgpu::RequestAdapterError),
+    Device(wgpu::RequestDeviceError),
+}
+
+impl fmt::Display for OffscreenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Adapter(error) => write!(f, "failed to request adapter: {error}"),
+            Self::Device(error) => write!(f, "failed to request device: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for OffscreenError {}
+
+impl From<wgpu::RequestAdapterError> for OffscreenError {
+    fn from(value: wgpu::RequestAdapterError) -> Self {
+        Self::Adapter(value)
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for OffscreenError {
+    fn from(value: wgpu::RequestDeviceError) -> Self {
+        Self::Device(value)
+    }
+}
+
+/// Render target that isn't backed by a window, useful for screenshot tests
+/// and thumbnails. It owns a device created with the same features and limits
+/// as the windowed one, so renderers built from it behave identically.
+pub struct OffscreenTarget {
+    pub instance: wgpu::Instance,
+    pub adapter: wgpu::Adapter,
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub depth_texture: Texture,
+}
+
+impl OffscreenTarget {
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// # Errors
+    ///
+    /// Returns an error if there is no suitable adapter or device creation
+    /// failed.
+    pub fn new(width: u32, height: u32) -> Result<Self, OffscreenError> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&crate::device_descriptor()))?;
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_texture = Texture::create_depth_texture(&device, width, height, "Offscreen Depth Texture");
+
+        Ok(Self {
+            instance,
+            adapter,
+            device,
+            queue,
+            texture,
+            view,
+            depth_texture,
+        })
+    }
+
+    pub fn size(&self) -> glam::UVec2 {
+        glam::UVec2::new(self.texture.width(), self.texture.height())
+    }
+
+    pub fn read_to_image(&self) -> Option<RgbaImage> {
+        read_texture_to_image(&self.device, &self.queue, &self.texture)
+    }
+}
+
+/// Copies `texture` back to the CPU. Returns [`None`] if the texture wasn't
+/// created with [`wgpu::TextureUsages::COPY_SRC`] or doesn't use an 8-bit RGBA
+/// or BGRA format.
+///
+/// Unlike OpenGL, wgpu textures start at the top-left corner, so rows are
+/// already in image order.
+#[allow(clippy::missing_panics_doc)]
+pub fn read_texture_to_image(device: &wgpu::Device, queue: &wgpu::Queue, texture: &wgpu::Texture) -> Option<RgbaImage> {
+    use wgpu::TextureFormat::{Bgra8Unorm, Bgra8UnormSrgb, Rgba8Unorm, Rgba8UnormSrgb};
+
+    let swap_channels = match texture.format() {
+        Rgba8Unorm | Rgba8UnormSrgb => false,
+        Bgra8Unorm | Bgra8UnormSrgb => true,
+        _ => return None,
+    };
+
+    if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+        return None;
+    }
+
+    let (width, height) = (texture.width(), texture.height());
+    let bytes_per_row = width * 4;
+    let padded_bytes_per_row = bytes_per_row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Readback Buffer"),
+        size: u64::from(padded_bytes_per_row * height),
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Readback Command Encoder"),
+    });
+
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfoBase {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(height),
+            },
+        },
+        texture.size(),
+    );
+
+    queue.submit([encoder.finish()]);
+
+    buffer.slice(..).map_async(wgpu::MapMode::Read, |_| ());
+
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+
+    let mut pixels = Vec::with_capacity((bytes_per_row * height) as usize);
+
+    {
+        let data = buffer.slice(..).get_mapped_range().unwrap();
+
+        for row in data.chunks_exact(padded_bytes_per_row as usize) {
+            pixels.extend_from_slice(&row[..bytes_per_row as usize]);
+        }
+    }
+
+    buffer.unmap();
+
+    if swap_channels {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+
+    RgbaImage::from_raw(width, height, pixels)
+}
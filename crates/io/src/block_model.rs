@@ -7,6 +7,15 @@ use serde::{
 
 use crate::{TexturePath, TextureRef};
 
+/// Animation of a face texture made of frames stacked vertically: the
+/// texture is `N` square frames tall, where `N` is its height divided by its
+/// width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FaceAnimation {
+    /// How long each frame is shown, in milliseconds.
+    pub frame_duration: u32,
+}
+
 /// Represents a single face (or side) of a [`BlockElement`].
 #[derive(Debug, Default, Serialize)]
 pub struct BlockFace {
@@ -23,6 +32,9 @@ pub struct BlockFace {
     /// Face, if there is a block on which this face will be "skipped"
     /// when creating a chunk mesh.
     pub cull_face: Option<Face>,
+    /// Makes the face cycle through the frames of its texture, see
+    /// [`FaceAnimation`].
+    pub animation: Option<FaceAnimation>,
 }
 
 impl<'de> Deserialize<'de> for BlockFace {
@@ -64,7 +76,8 @@ impl<'de> Deserialize<'de> for BlockFace {
                         "tint" => value.tint = map.next_value()?,
                         "uv" => value.uv = Some(map.next_value()?),
                         "cull_face" => value.cull_face = Some(map.next_value()?),
-                        field => Err(Error::unknown_field(field, &["texture", "uv", "cull_face"]))?,
+                        "animation" => value.animation = Some(map.next_value()?),
+                        field => Err(Error::unknown_field(field, &["texture", "tint", "uv", "cull_face", "animation"]))?,
                     }
                 }
 
@@ -161,7 +174,7 @@ pub enum Faces {
 
 #[cfg(test)]
 mod tests {
-    use crate::BlockModel;
+    use crate::{BlockFace, BlockModel, FaceAnimation};
 
     #[test]
     fn test_block_model_parsing() {
@@ -169,4 +182,12 @@ mod tests {
 
         assert!(serde_json::from_slice::<BlockModel>(data).is_ok());
     }
+
+    #[test]
+    fn test_face_animation_parsing() {
+        let face = serde_json::from_str::<BlockFace>(r##"{ "texture": "#all", "animation": { "frame_duration": 100 } }"##).unwrap();
+
+        assert_eq!(face.animation, Some(FaceAnimation { frame_duration: 100 }));
+        assert_eq!(serde_json::from_str::<BlockFace>(r##""#all""##).unwrap().animation, None);
+    }
 }
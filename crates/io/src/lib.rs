@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize, de::Visitor};
 pub use serde_json::Error as JsonError;
 
 pub use self::{
-    block_model::{BlockElement, BlockFace, BlockModel, ElementRotation, Faces},
+    block_model::{BlockElement, BlockFace, BlockModel, ElementRotation, FaceAnimation, Faces},
     block_states::{
         BlockState, BlockStateValidationError, BlockStates, BlockStatesValidationError, NumericProperty, Property, PropertyRegistry, PropertyType,
         PropertyValue,
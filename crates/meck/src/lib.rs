@@ -144,9 +144,12 @@ impl<K: Hash + Eq> TextureViewAtlas<K> {
     }
 }
 
+/// Atlas that packs textures into shelves: rows filled from left to right,
+/// each as tall as the tallest texture placed on it.
 pub struct TextureAtlas<K: Hash + Eq> {
     texture_map: HashMap<K, (glam::UVec2, glam::UVec2, u8)>,
     next_texture_offset: glam::UVec2,
+    shelf_height: u32,
     spacing: u32,
     mipmaps: Vec<RgbaImage>,
 }
@@ -156,6 +159,7 @@ impl<K: Hash + Eq> TextureAtlas<K> {
         Self {
             texture_map: HashMap::new(),
             next_texture_offset: glam::UVec2::ZERO,
+            shelf_height: 0,
             spacing: 0,
             mipmaps: vec![RgbaImage::new(size, size)],
         }
@@ -214,6 +218,11 @@ impl<K: Hash + Eq> TextureAtlas<K> {
         self.texture_map.len()
     }
 
+    /// Height of the part of the atlas taken up by shelves so far.
+    pub const fn used_height(&self) -> u32 {
+        self.next_texture_offset.y + self.shelf_height
+    }
+
     pub fn generate_mipmaps(&mut self, level: usize) {
         for i in 1..=level {
             self.generate_mipmap(i);
@@ -255,8 +264,36 @@ impl<K: Hash + Eq> TextureAtlas<K> {
         self.texture_map.contains_key(key)
     }
 
+    /// Reserves space for a texture of the given size without drawing
+    /// anything there.
     pub fn step_next(&mut self, size: glam::UVec2) {
-        self.next_texture_offset = self.next_texture_offset.with_x(self.next_texture_offset.x + size.x + self.spacing);
+        self.allocate(size);
+    }
+
+    /// Finds a place for a texture of the given size, starting a new shelf
+    /// when it doesn't fit on the current one.
+    fn allocate(&mut self, size: glam::UVec2) -> glam::UVec2 {
+        if self.next_texture_offset.x > 0 && self.next_texture_offset.x + size.x > self.main_texture().width() {
+            self.next_texture_offset = glam::UVec2::new(0, self.next_texture_offset.y + self.shelf_height + self.spacing);
+            self.shelf_height = 0;
+        }
+
+        let origin = self.next_texture_offset;
+
+        self.shelf_height = self.shelf_height.max(size.y);
+        self.next_texture_offset.x += size.x + self.spacing;
+
+        origin
+    }
+
+    fn write(&mut self, origin: glam::UVec2, image: &RgbaImage) {
+        let mut sub_image = self.main_level_mut().sub_image(origin.x, origin.y, image.width(), image.height());
+
+        for k in 0..image.height() {
+            for i in 0..image.width() {
+                sub_image.put_pixel(i, k, image[(i, image.height() - 1 - k)]);
+            }
+        }
     }
 
     /// # Errors
@@ -270,21 +307,38 @@ impl<K: Hash + Eq> TextureAtlas<K> {
 
         let alpha = image.pixels().map(|pixel| pixel.0[3]).min().unwrap_or(0);
         let size = glam::UVec2::from(image.dimensions());
-        let offset = (self.next_texture_offset, size);
+        let origin = self.allocate(size);
 
-        let main_image = self.main_level_mut();
-        let mut sub_image = main_image.sub_image(offset.0.x, 0, offset.1.x, offset.1.y);
+        self.write(origin, image);
+        self.texture_map.insert(key, (origin, size, alpha));
 
-        for k in 0..image.height() {
-            for i in 0..image.width() {
-                sub_image.put_pixel(i, k, image[(i, image.height() - 1 - k)]);
-            }
+        size
+    }
+
+    /// Overwrites the pixels of an already packed texture in place. Returns
+    /// `false` if `key` isn't packed or `image` has a different size, since
+    /// moving it would shift every texture packed after it.
+    pub fn replace<Q: ?Sized + Hash + Eq>(&mut self, key: &Q, image: &RgbaImage) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        let Some((origin, size, _)) = self.get_texture_rect(key) else {
+            return false;
+        };
+
+        if size != glam::UVec2::from(image.dimensions()) {
+            return false;
         }
 
-        self.texture_map.insert(key, (offset.0, offset.1, alpha));
-        self.step_next(size);
+        let alpha = image.pixels().map(|pixel| pixel.0[3]).min().unwrap_or(0);
 
-        size
+        self.write(origin, image);
+
+        if let Some(texture) = self.texture_map.get_mut(key) {
+            texture.2 = alpha;
+        }
+
+        true
     }
 
     /// # Errors
@@ -297,22 +351,80 @@ impl<K: Hash + Eq> TextureAtlas<K> {
         }
 
         let alpha = image.pixels().map(|pixel| pixel.0[3]).min().unwrap_or(0);
-        let offset = (self.next_texture_offset, glam::UVec2::from(image.dimensions()));
+        let texture_size = glam::UVec2::from(image.dimensions());
+        let origin = self.allocate(texture_size);
 
-        let main_image = self.main_level_mut();
-        let mut sub_image = main_image.sub_image(offset.0.x, 0, offset.1.x, offset.1.y);
+        self.write(origin, image);
+        self.texture_map.insert(key, (origin, texture_size, alpha));
 
-        for k in 0..image.height() {
-            for i in 0..image.width() {
-                sub_image.put_pixel(i, k, image[(i, image.height() - 1 - k)]);
-            }
-        }
+        let size = self.size();
 
-        self.texture_map.insert(key, (offset.0, offset.1, alpha));
-        self.step_next(image.dimensions().into());
+        (origin.as_vec2() / size, texture_size.as_vec2() / size, alpha)
+    }
 
-        let size = self.size();
+    /// Same as calling [`Self::append`] for every item, but places the
+    /// tallest textures first, so that shelves waste less space. Returns the
+    /// UVs of every item by its key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images don't fit into the atlas.
+    pub fn append_batch<I: IntoIterator<Item = (K, RgbaImage)>>(&mut self, items: I) -> HashMap<K, (glam::Vec2, glam::Vec2, u8)>
+    where
+        K: Clone,
+    {
+        let mut items = items.into_iter().collect::<Vec<_>>();
 
-        (offset.0.as_vec2() / size, offset.1.as_vec2() / size, alpha)
+        items.sort_by_key(|(_, image)| std::cmp::Reverse(image.height()));
+        items
+            .into_iter()
+            .map(|(key, image)| {
+                let uv = self.append(key.clone(), &image);
+
+                (key, uv)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use crate::TextureAtlas;
+
+    fn images() -> Vec<(u8, RgbaImage)> {
+        (0..8)
+            .map(|i| {
+                let height = if i % 2 == 0 { 4 } else { 32 };
+
+                (i, RgbaImage::from_pixel(16, height, Rgba([i, 0, 0, 255])))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_append_batch_packs_tighter() {
+        let mut sequential = TextureAtlas::new(64);
+        let mut batch = TextureAtlas::new(64);
+
+        let sequential_uvs = images()
+            .into_iter()
+            .map(|(key, image)| (key, sequential.append(key, &image)))
+            .collect::<Vec<_>>();
+
+        let batch_uvs = batch.append_batch(images());
+
+        assert_eq!(sequential.used_height(), 64);
+        assert_eq!(batch.used_height(), 36);
+        assert_eq!(batch_uvs.len(), sequential_uvs.len());
+
+        for (key, image) in images() {
+            let (origin, size, _) = batch.get_texture_rect(&key).unwrap();
+
+            assert_eq!(batch_uvs[&key], batch.get_texture_uv(&key).unwrap());
+            assert_eq!(size, glam::UVec2::from(image.dimensions()));
+            assert_eq!(batch.main_texture()[(origin.x, origin.y)], image[(0, 0)]);
+        }
     }
 }
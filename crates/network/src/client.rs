@@ -3,7 +3,7 @@ use std::io;
 use futures::{SinkExt, StreamExt};
 use tokio::net::{TcpStream, ToSocketAddrs};
 
-use super::{InStream, IncomingPacket, OutSink, OutgoingPacket, wrap_stream};
+use super::{InStream, IncomingPacket, OutSink, OutgoingPacket, PROTOCOL_VERSION, ProtocolError, handshake::client_handshake, wrap_stream};
 
 pub struct Client(pub InStream<OutgoingPacket>, pub OutSink<IncomingPacket>);
 
@@ -15,8 +15,14 @@ impl Client {
         Self(in_stream, out_stream)
     }
 
-    pub async fn connect<T: ToSocketAddrs>(addr: T) -> Result<Self, io::Error> {
-        TcpStream::connect(addr).await.map(Self::new)
+    /// Connects to the server and negotiates the protocol version before any
+    /// packet is exchanged.
+    pub async fn connect<T: ToSocketAddrs>(addr: T) -> Result<Self, ProtocolError> {
+        let mut stream = TcpStream::connect(addr).await?;
+
+        client_handshake(&mut stream, PROTOCOL_VERSION).await?;
+
+        Ok(Self::new(stream))
     }
 
     pub async fn receive(&mut self) -> Option<Result<OutgoingPacket, io::Error>> {
@@ -0,0 +1,84 @@
+use core::fmt;
+use std::io;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Version of the packet protocol, bump it whenever [`crate::IncomingPacket`]
+/// or [`crate::OutgoingPacket`] change in an incompatible way.
+pub const PROTOCOL_VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    Io(io::Error),
+    VersionMismatch { client: u8, server: u8 },
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "connection failed: {error}"),
+            Self::VersionMismatch { client, server } => write!(f, "protocol version mismatch: client is on {client}, server is on {server}"),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<io::Error> for ProtocolError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Sends the client's protocol version and checks it against the one the
+/// server answers with.
+pub async fn client_handshake<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S, version: u8) -> Result<(), ProtocolError> {
+    stream.write_u8(version).await?;
+    stream.flush().await?;
+
+    let server = stream.read_u8().await?;
+
+    if server == version {
+        Ok(())
+    } else {
+        Err(ProtocolError::VersionMismatch { client: version, server })
+    }
+}
+
+/// Reads the client's protocol version and answers with the server's one, so
+/// that both sides can report a mismatch.
+pub async fn server_handshake<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S, version: u8) -> Result<(), ProtocolError> {
+    let client = stream.read_u8().await?;
+
+    stream.write_u8(version).await?;
+    stream.flush().await?;
+
+    if client == version {
+        Ok(())
+    } else {
+        Err(ProtocolError::VersionMismatch { client, server: version })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProtocolError, client_handshake, server_handshake};
+
+    #[tokio::test]
+    async fn test_matching_versions() {
+        let (mut client, mut server) = tokio::io::duplex(8);
+        let (client, server) = tokio::join!(client_handshake(&mut client, 1), server_handshake(&mut server, 1));
+
+        assert!(client.is_ok());
+        assert!(server.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_versions() {
+        let (mut client, mut server) = tokio::io::duplex(8);
+        let (client, server) = tokio::join!(client_handshake(&mut client, 1), server_handshake(&mut server, 2));
+
+        assert!(matches!(client, Err(ProtocolError::VersionMismatch { client: 1, server: 2 })));
+        assert!(matches!(server, Err(ProtocolError::VersionMismatch { client: 1, server: 2 })));
+    }
+}
@@ -1,4 +1,5 @@
 mod client;
+mod handshake;
 mod packet;
 mod protocol;
 mod server;
@@ -7,6 +8,7 @@ pub use uuid::Uuid;
 
 pub use self::{
     client::Client,
+    handshake::{PROTOCOL_VERSION, ProtocolError},
     packet::{IncomingPacket, OutgoingPacket, Player},
     protocol::{InStream, OutSink, wrap_stream},
     server::ServerConnection,
@@ -3,7 +3,7 @@ use std::io;
 use futures::{SinkExt, StreamExt};
 use tokio::net::TcpStream;
 
-use super::{InStream, IncomingPacket, OutSink, OutgoingPacket, wrap_stream};
+use super::{InStream, IncomingPacket, OutSink, OutgoingPacket, PROTOCOL_VERSION, ProtocolError, handshake::server_handshake, wrap_stream};
 
 pub struct ServerConnection(InStream<IncomingPacket>, OutSink<OutgoingPacket>);
 
@@ -15,6 +15,14 @@ impl ServerConnection {
         Self(in_stream, out_stream)
     }
 
+    /// Negotiates the protocol version with a freshly accepted client before
+    /// any packet is exchanged.
+    pub async fn accept(mut stream: TcpStream) -> Result<Self, ProtocolError> {
+        server_handshake(&mut stream, PROTOCOL_VERSION).await?;
+
+        Ok(Self::new(stream))
+    }
+
     pub async fn receive(&mut self) -> Option<Result<IncomingPacket, io::Error>> {
         self.0.next().await
     }
@@ -11,7 +11,7 @@ fn get_intermediate_with_x_value(a: glam::DVec3, b: glam::DVec3, x: f64) -> Opti
         let f = (x - a.x) / diff.x;
 
         if (0.0..=1.0).contains(&f) {
-            Some(glam::DVec3::new(diff.x.mul_add(f, a.x), diff.x.mul_add(f, a.y), diff.x.mul_add(f, a.z)))
+            Some(glam::DVec3::new(diff.x.mul_add(f, a.x), diff.y.mul_add(f, a.y), diff.z.mul_add(f, a.z)))
         } else {
             None
         }
@@ -27,7 +27,7 @@ fn get_intermediate_with_y_value(a: glam::DVec3, b: glam::DVec3, y: f64) -> Opti
         let f = (y - a.y) / diff.y;
 
         if (0.0..=1.0).contains(&f) {
-            Some(glam::DVec3::new(diff.y.mul_add(f, a.x), diff.y.mul_add(f, a.y), diff.y.mul_add(f, a.z)))
+            Some(glam::DVec3::new(diff.x.mul_add(f, a.x), diff.y.mul_add(f, a.y), diff.z.mul_add(f, a.z)))
         } else {
             None
         }
@@ -210,3 +210,60 @@ impl From<Cube> for Aabb {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mavelin_shared::Face;
+
+    use crate::Aabb;
+
+    fn intercept(from: [f64; 3], to: [f64; 3]) -> Option<(glam::DVec3, Face)> {
+        Aabb::cube(glam::DVec3::ZERO)
+            .calculate_intercept(glam::DVec3::from_array(from), glam::DVec3::from_array(to))
+            .map(|result| (result.hit_vec, result.hit_side))
+    }
+
+    #[track_caller]
+    fn assert_hit(from: [f64; 3], to: [f64; 3], hit: [f64; 3], face: Face) {
+        let (hit_vec, hit_side) = intercept(from, to).unwrap_or_else(|| panic!("ray from {from:?} to {to:?} missed"));
+
+        assert_eq!(hit_side, face, "ray from {from:?} to {to:?}");
+        assert!(
+            hit_vec.abs_diff_eq(glam::DVec3::from_array(hit), 1e-9),
+            "ray from {from:?} to {to:?} hit {hit_vec}"
+        );
+    }
+
+    #[test]
+    fn test_intercept_from_every_direction() {
+        assert_hit([-1.0, 0.25, 0.75], [2.0, 0.25, 0.75], [0.0, 0.25, 0.75], Face::Left);
+        assert_hit([2.0, 0.25, 0.75], [-1.0, 0.25, 0.75], [1.0, 0.25, 0.75], Face::Right);
+        assert_hit([0.25, -1.0, 0.75], [0.25, 2.0, 0.75], [0.25, 0.0, 0.75], Face::Bottom);
+        assert_hit([0.25, 2.0, 0.75], [0.25, -1.0, 0.75], [0.25, 1.0, 0.75], Face::Top);
+        assert_hit([0.25, 0.75, -1.0], [0.25, 0.75, 2.0], [0.25, 0.75, 0.0], Face::Back);
+        assert_hit([0.25, 0.75, 2.0], [0.25, 0.75, -1.0], [0.25, 0.75, 1.0], Face::Front);
+    }
+
+    #[test]
+    fn test_intercept_diagonal() {
+        assert_hit([-1.0, -0.5, 0.0], [1.0, 1.5, 2.0], [0.0, 0.5, 1.0], Face::Left);
+        assert_hit([0.5, 2.0, 1.5], [0.5, 0.0, -0.5], [0.5, 1.0, 0.5], Face::Top);
+    }
+
+    #[test]
+    fn test_intercept_from_inside_hits_exit_face() {
+        assert_hit([0.5, 0.5, 0.5], [0.5, 3.0, 0.5], [0.5, 1.0, 0.5], Face::Top);
+        assert_hit([0.5, 0.5, 0.5], [-2.0, 0.5, 0.5], [0.0, 0.5, 0.5], Face::Left);
+    }
+
+    #[test]
+    fn test_intercept_parallel_to_face() {
+        assert_eq!(intercept([-1.0, 2.0, 0.5], [2.0, 2.0, 0.5]), None);
+        assert_hit([0.5, 0.5, -1.0], [0.5, 0.5, 0.5], [0.5, 0.5, 0.0], Face::Back);
+    }
+
+    #[test]
+    fn test_intercept_misses_short_ray() {
+        assert_eq!(intercept([-2.0, 0.5, 0.5], [-1.0, 0.5, 0.5]), None);
+    }
+}
@@ -11,6 +11,9 @@ pub struct PhysicsConfig {
     pub linear_damping: f32,
     pub vertical_damping: f32,
     pub friction: f32,
+    /// Height of the ledges a grounded object walks onto instead of being
+    /// stopped by them. The default value is `0.6`.
+    pub step_height: f32,
 }
 
 impl Default for PhysicsConfig {
@@ -20,6 +23,7 @@ impl Default for PhysicsConfig {
             linear_damping: 0.5,
             vertical_damping: 1.0,
             friction: 1.0,
+            step_height: 0.6,
         }
     }
 }
@@ -89,26 +89,62 @@ impl<T: AabbSource> PhysicsContext<T> {
         }
     }
 
-    fn calc_step_height(&self, pos: glam::Vec3, half: glam::Vec3, step_height: f32) -> f32 {
-        if step_height > 0.0 {
-            for i_x in 0..=((half.x - Self::E) * 2.0 / Self::S) as i32 {
-                let x = (i_x as f32).mul_add(Self::S, pos.x - half.x + Self::E);
+    /// Whether no obstacle overlaps the box from `min` to `max`, sampled every
+    /// [`Self::S`] like the collision checks.
+    fn is_free(&self, min: glam::Vec3, max: glam::Vec3) -> bool {
+        let steps = ((max - min - Self::E * 2.0) / Self::S).ceil().max(glam::Vec3::ZERO).as_ivec3();
 
-                for i_z in 0..=((half.z - Self::E) * 2.0 / Self::S) as i32 {
-                    let z = (i_z as f32).mul_add(Self::S, pos.z - half.z + Self::E);
+        for i_x in 0..=steps.x {
+            let x = (i_x as f32).mul_add(Self::S, min.x + Self::E).min(max.x - Self::E);
+
+            for i_y in 0..=steps.y {
+                let y = (i_y as f32).mul_add(Self::S, min.y + Self::E).min(max.y - Self::E);
 
-                    if self.is_obstacle(glam::Vec3::new(x, pos.y + half.y + step_height, z)).is_some() {
-                        return 0.0;
+                for i_z in 0..=steps.z {
+                    let z = (i_z as f32).mul_add(Self::S, min.z + Self::E).min(max.z - Self::E);
+
+                    if self.is_obstacle(glam::Vec3::new(x, y, z)).is_some() {
+                        return false;
                     }
                 }
             }
         }
 
-        step_height
+        true
+    }
+
+    /// Returns `step_height` if the body can be lifted that much: there has to
+    /// be headroom above it, and room for the lifted body in the direction it
+    /// is moving. Returns `0.0` otherwise, so that ledges block it as usual.
+    fn calc_step_height(&self, body: &PhysicsBody, half: glam::Vec3, step_height: f32) -> f32 {
+        if step_height <= 0.0 {
+            return 0.0;
+        }
+
+        let lift = glam::Vec3::new(0.0, step_height, 0.0);
+        let head = body.position.with_y(body.position.y + half.y);
+
+        if !self.is_free(head - half.with_y(0.0), head + half.with_y(0.0) + lift) {
+            return 0.0;
+        }
+
+        let direction = |velocity: f32| {
+            if velocity > 0.0 {
+                1.0
+            } else if velocity < 0.0 {
+                -1.0
+            } else {
+                0.0
+            }
+        };
+
+        let ahead = body.position + lift + glam::Vec3::new(direction(body.velocity.x), 0.0, direction(body.velocity.z)) * Self::E * 2.0;
+
+        if self.is_free(ahead - half, ahead + half) { step_height } else { 0.0 }
     }
 
     fn collision_calc(&self, body: &mut PhysicsBody, half: glam::Vec3, step_height: f32) {
-        let step_height = self.calc_step_height(body.position, half, step_height);
+        let step_height = self.calc_step_height(body, half, step_height);
 
         self.calc_collision_neg::<0, 1, 2>(body, half, step_height);
         self.calc_collision_pos::<0, 1, 2>(body, half, step_height);
@@ -188,7 +224,15 @@ impl<T: AabbSource> PhysicsContext<T> {
 
             body.velocity += Self::GRAVITY * dt * body.config.gravity_scale;
 
-            self.collision_calc(body, half, if prev_grounded && body.config.gravity_scale > 0.0 { 0.5 } else { 0.0 });
+            self.collision_calc(
+                body,
+                half,
+                if prev_grounded && body.config.gravity_scale > 0.0 {
+                    body.config.step_height
+                } else {
+                    0.0
+                },
+            );
 
             body.position += body.velocity * dt * 1.25 + Self::GRAVITY * body.config.gravity_scale * dt * dt * 0.5;
 
@@ -205,3 +249,67 @@ impl<T: AabbSource> PhysicsContext<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aabb, AabbSource, PhysicsBody, PhysicsContext};
+
+    /// Flat ground at `y = 0` with a one block high step at `x = 2`, and
+    /// optionally a ceiling over the step that leaves no room to stand on it.
+    struct Step {
+        ceiling: bool,
+    }
+
+    impl AabbSource for Step {
+        fn get_aabb(&self, position: glam::Vec3) -> Option<Aabb> {
+            self.get_block_aabb(position.floor().as_ivec3())
+        }
+
+        fn get_block_aabb(&self, position: glam::IVec3) -> Option<Aabb> {
+            let solid = position.y == -1 || (position.x == 2 && (position.y == 0 || (self.ceiling && position.y == 2)));
+
+            solid.then(|| Aabb::cube(glam::DVec3::ZERO))
+        }
+    }
+
+    fn walk_into_step(ceiling: bool, step_height: Option<f32>) -> PhysicsBody {
+        let context = PhysicsContext::new(Step { ceiling });
+        let mut body = PhysicsBody::new(glam::Vec3::new(0.5, 0.9, 0.5), glam::Vec3::new(0.6, 1.8, 0.6));
+
+        if let Some(step_height) = step_height {
+            body.config.step_height = step_height;
+        }
+
+        for _ in 0..30 {
+            body.velocity.x = 4.0;
+
+            context.physics_step(&mut body, 1.0 / 60.0);
+        }
+
+        body
+    }
+
+    #[test]
+    fn test_steps_onto_block() {
+        let body = walk_into_step(false, Some(1.0));
+
+        assert!(body.position.x > 2.0);
+        assert!((body.position.y - 1.9).abs() < 0.05, "{}", body.position.y);
+    }
+
+    #[test]
+    fn test_ceiling_prevents_step() {
+        let body = walk_into_step(true, Some(1.0));
+
+        assert!(body.position.x < 2.0);
+        assert!((body.position.y - 0.9).abs() < 0.05, "{}", body.position.y);
+    }
+
+    #[test]
+    fn test_default_step_height_stays_below_block() {
+        let body = walk_into_step(false, None);
+
+        assert!(body.position.x < 2.0);
+        assert!((body.position.y - 0.9).abs() < 0.05, "{}", body.position.y);
+    }
+}
@@ -174,3 +174,48 @@ impl<T: AabbSource> PhysicsContext<T> {
         self.source.get_block_aabb(position)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mavelin_shared::Face;
+
+    use crate::{Aabb, AabbSource, PhysicsContext};
+
+    /// A world holding a single full block at `0` `5` `0`.
+    struct SingleBlock;
+
+    impl SingleBlock {
+        const POSITION: glam::IVec3 = glam::IVec3::new(0, 5, 0);
+    }
+
+    impl AabbSource for SingleBlock {
+        fn get_aabb(&self, position: glam::Vec3) -> Option<Aabb> {
+            self.get_block_aabb(position.floor().as_ivec3())
+        }
+
+        fn get_block_aabb(&self, position: glam::IVec3) -> Option<Aabb> {
+            (position == Self::POSITION).then(|| Aabb::cube(glam::DVec3::ZERO))
+        }
+    }
+
+    fn raycast_up(reach: f64) -> Option<crate::RayCastResult> {
+        let origin = glam::DVec3::new(0.5, 0.5, 0.5);
+
+        PhysicsContext::new(SingleBlock).raycast(origin, origin + glam::DVec3::Y * reach, true)
+    }
+
+    #[test]
+    fn test_raycast_hits_block_within_reach() {
+        let result = raycast_up(20.0).expect("block should be hit");
+
+        assert!(result.is_block());
+        assert_eq!(result.position, SingleBlock::POSITION);
+        assert_eq!(result.hit_side, Face::Bottom);
+        assert!((result.hit_vec.y - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_raycast_stops_at_reach() {
+        assert!(!raycast_up(4.0).is_some_and(|result| result.is_block()));
+    }
+}
@@ -3,6 +3,10 @@ use crate::AsValue;
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// Color type represented as RGBA
+///
+/// Colors are stored with straight (not premultiplied) alpha, which is what
+/// the render pipelines blend with. Use [`Color::premultiplied`] when handing
+/// a color to something that expects premultiplied alpha instead.
 pub struct Color([u8; 4]);
 
 #[cfg(feature = "serde")]
@@ -17,19 +21,32 @@ impl<'de> serde::Deserialize<'de> for Color {
             type Value = Color;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("valid hex color")
+                formatter.write_str("valid hex color (#RRGGBB or #RRGGBBAA) or color name")
             }
 
             fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
             where
                 E: serde::de::Error,
             {
-                let hex = v.strip_prefix('#').ok_or_else(|| serde::de::Error::custom("color should start with #"))?;
-                let red = u8::from_str_radix(&hex[0..2], 16).map_err(|_| serde::de::Error::custom("invalid red component"))?;
-                let green = u8::from_str_radix(&hex[2..4], 16).map_err(|_| serde::de::Error::custom("invalid green component"))?;
-                let blue = u8::from_str_radix(&hex[4..6], 16).map_err(|_| serde::de::Error::custom("invalid blue component"))?;
+                let Some(hex) = v.strip_prefix('#') else {
+                    return Color::from_name(v).ok_or_else(|| serde::de::Error::custom("color should start with # or be a known color name"));
+                };
+                if hex.len() != 6 && hex.len() != 8 {
+                    return Err(serde::de::Error::custom("hex color should be in #RRGGBB or #RRGGBBAA format"));
+                }
+
+                let component = |range: std::ops::Range<usize>, name: &str| {
+                    hex.get(range)
+                        .and_then(|value| u8::from_str_radix(value, 16).ok())
+                        .ok_or_else(|| serde::de::Error::custom(format!("invalid {name} component")))
+                };
 
-                Ok(Color::new(red, green, blue, 255))
+                let red = component(0..2, "red")?;
+                let green = component(2..4, "green")?;
+                let blue = component(4..6, "blue")?;
+                let alpha = if hex.len() == 8 { component(6..8, "alpha")? } else { 255 };
+
+                Ok(Color::new(red, green, blue, alpha))
             }
         }
 
@@ -43,7 +60,11 @@ impl serde::Serialize for Color {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&format!("#{:02X}{:02X}{:02X}", self.0[0], self.0[1], self.0[2]))
+        if self.0[3] == 255 {
+            serializer.serialize_str(&format!("#{:02X}{:02X}{:02X}", self.0[0], self.0[1], self.0[2]))
+        } else {
+            serializer.serialize_str(&format!("#{:02X}{:02X}{:02X}{:02X}", self.0[0], self.0[1], self.0[2], self.0[3]))
+        }
     }
 }
 
@@ -339,12 +360,53 @@ impl Color {
     pub const BLACK: Self = Self([0, 0, 0, 255]);
     pub const BLUE: Self = Self([0, 0, 255, 255]);
     pub const BROWN: Self = Self([165, 42, 42, 255]);
+    pub const CYAN: Self = Self([0, 255, 255, 255]);
+    pub const GRAY: Self = Self([128, 128, 128, 255]);
     pub const GREEN: Self = Self([0, 255, 0, 255]);
+    pub const MAGENTA: Self = Self([255, 0, 255, 255]);
+    /// Colors that can be looked up by name with [`Color::from_name`].
+    pub const NAMED: &[(&str, Self)] = &[
+        ("black", Self::BLACK),
+        ("blue", Self::BLUE),
+        ("brown", Self::BROWN),
+        ("cornflowerblue", Self::rgb(100, 149, 237)),
+        ("crimson", Self::rgb(220, 20, 60)),
+        ("cyan", Self::CYAN),
+        ("darkgray", Self::rgb(169, 169, 169)),
+        ("darkgreen", Self::rgb(0, 100, 0)),
+        ("gold", Self::rgb(255, 215, 0)),
+        ("gray", Self::GRAY),
+        ("green", Self::GREEN),
+        ("grey", Self::GRAY),
+        ("lightblue", Self::rgb(173, 216, 230)),
+        ("lightgray", Self::rgb(211, 211, 211)),
+        ("lime", Self::GREEN),
+        ("magenta", Self::MAGENTA),
+        ("navy", Self::rgb(0, 0, 128)),
+        ("olive", Self::rgb(128, 128, 0)),
+        ("orange", Self::ORANGE),
+        ("pink", Self::rgb(255, 192, 203)),
+        ("purple", Self::PURPLE),
+        ("red", Self::RED),
+        ("silver", Self::rgb(192, 192, 192)),
+        ("skyblue", Self::rgb(135, 206, 235)),
+        ("teal", Self::rgb(0, 128, 128)),
+        ("transparent", Self::TRANSPARENT),
+        ("white", Self::WHITE),
+        ("yellow", Self::YELLOW),
+    ];
+    pub const ORANGE: Self = Self([255, 165, 0, 255]);
     pub const PURPLE: Self = Self([128, 0, 128, 255]);
     pub const RED: Self = Self([255, 0, 0, 255]);
+    pub const TRANSPARENT: Self = Self([0, 0, 0, 0]);
     pub const WHITE: Self = Self([255, 255, 255, 255]);
     pub const YELLOW: Self = Self([255, 255, 0, 255]);
 
+    /// Looks up a color by its CSS-like name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMED.iter().find_map(|&(known, color)| known.eq_ignore_ascii_case(name).then_some(color))
+    }
+
     #[inline]
     pub const fn get_red(&self) -> u8 {
         self.0[0]
@@ -467,8 +529,68 @@ impl Color {
         ])
     }
 
+    /// Returns the color with its red, green and blue components multiplied
+    /// by its alpha.
+    #[must_use]
+    #[inline]
+    pub const fn premultiplied(&self) -> Self {
+        const fn multiply(component: u8, alpha: u8) -> u8 {
+            ((component as u16 * alpha as u16 + 127) / 255) as u8
+        }
+
+        let alpha = self.0[3];
+
+        Self([multiply(self.0[0], alpha), multiply(self.0[1], alpha), multiply(self.0[2], alpha), alpha])
+    }
+
     #[inline]
     pub fn as_rgb_hex(&self) -> String {
         format!("{:02x}{:02x}{:02x}", self.0[0], self.0[1], self.0[2])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{AsValue, Color};
+
+    #[test]
+    fn test_premultiplied() {
+        let [red, green, blue, alpha]: [f32; 4] = Color::WHITE.with_alpha(0.5).premultiplied().as_value();
+
+        for component in [red, green, blue, alpha] {
+            assert!((component - 0.5).abs() <= 1.0 / 255.0);
+        }
+
+        assert_eq!(Color::RED.premultiplied(), Color::RED);
+        assert_eq!(Color::new(200, 100, 50, 0).premultiplied(), Color::new(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Color::from_name("red"), Some(Color::RED));
+        assert_eq!(Color::from_name("CornflowerBlue"), Some(Color::rgb(100, 149, 237)));
+        assert_eq!(Color::from_name("TRANSPARENT"), Some(Color::TRANSPARENT));
+        assert_eq!(Color::from_name("grey"), Color::from_name("gray"));
+        assert_eq!(Color::from_name("notacolor"), None);
+        assert_eq!(Color::from_name(""), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_hex() {
+        use serde::{
+            Deserialize,
+            de::value::{Error, StrDeserializer},
+        };
+
+        let parse = |value: &str| Color::deserialize(StrDeserializer::<Error>::new(value));
+
+        assert_eq!(parse("#FF8000").unwrap(), Color::new(255, 128, 0, 255));
+        assert_eq!(parse("#FF800080").unwrap(), Color::new(255, 128, 0, 128));
+        assert_eq!(parse("red").unwrap(), Color::RED);
+
+        for invalid in ["#fff", "#", "#FF80001", "#GG0000", "#ффф"] {
+            assert!(parse(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -185,4 +185,108 @@ impl Face {
             Self::Front | Self::Back => Axis::Z,
         }
     }
+
+    /// Rotates the face by 90° steps about `axis`, counterclockwise when
+    /// looking from the positive end of the axis towards the origin. Negative
+    /// turns rotate clockwise; faces along `axis` stay unchanged.
+    #[must_use]
+    #[inline]
+    pub const fn rotated(self, axis: Axis, quarter_turns: i8) -> Self {
+        const CYCLES: [[Face; 4]; 3] = [
+            [Face::Top, Face::Front, Face::Bottom, Face::Back],
+            [Face::Right, Face::Back, Face::Left, Face::Front],
+            [Face::Right, Face::Top, Face::Left, Face::Bottom],
+        ];
+
+        let cycle = CYCLES[axis as usize];
+        let turns = quarter_turns.rem_euclid(4) as usize;
+        let mut i = 0;
+
+        while i < cycle.len() {
+            if cycle[i] as usize == self as usize {
+                return cycle[(i + turns) % cycle.len()];
+            }
+
+            i += 1;
+        }
+
+        self
+    }
+
+    /// Returns the face whose normal is most aligned with `direction`.
+    #[must_use]
+    #[inline]
+    #[cfg(feature = "geometry")]
+    pub fn from_look_direction(direction: glam::Vec3) -> Self {
+        let abs = direction.abs();
+
+        if abs.x >= abs.y && abs.x >= abs.z {
+            Self::from_axis_value(Axis::X, direction.x > 0.0)
+        } else if abs.y >= abs.z {
+            Self::from_axis_value(Axis::Y, direction.y > 0.0)
+        } else {
+            Self::from_axis_value(Axis::Z, direction.z > 0.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Axis, Face};
+
+    #[test]
+    fn test_rotated_quarter_turns() {
+        let cycles = [
+            (Axis::X, [Face::Top, Face::Front, Face::Bottom, Face::Back]),
+            (Axis::Y, [Face::Right, Face::Back, Face::Left, Face::Front]),
+            (Axis::Z, [Face::Right, Face::Top, Face::Left, Face::Bottom]),
+        ];
+
+        for (axis, cycle) in cycles {
+            for (i, &face) in cycle.iter().enumerate() {
+                for turns in 0..4 {
+                    assert_eq!(face.rotated(axis, turns as i8), cycle[(i + turns) % 4], "{face} about {axis:?} by {turns}");
+                }
+
+                assert_eq!(face.rotated(axis, -1), cycle[(i + 3) % 4]);
+                assert_eq!(face.rotated(axis, 5), cycle[(i + 1) % 4]);
+            }
+
+            for face in Face::ALL.into_iter().filter(|face| face.as_axis() == axis) {
+                for turns in 0..4 {
+                    assert_eq!(face.rotated(axis, turns), face);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "geometry")]
+    fn test_rotated_matches_normals() {
+        let rotations = [
+            (Axis::X, glam::Mat3::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+            (Axis::Y, glam::Mat3::from_rotation_y(std::f32::consts::FRAC_PI_2)),
+            (Axis::Z, glam::Mat3::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+        ];
+
+        for (axis, rotation) in rotations {
+            for face in Face::ALL {
+                let normal = (rotation * face.as_normal().as_vec3()).round().as_ivec3();
+
+                assert_eq!(face.rotated(axis, 1).as_normal(), normal);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "geometry")]
+    fn test_from_look_direction() {
+        for face in Face::ALL {
+            assert_eq!(Face::from_look_direction(face.as_normal().as_vec3()), face);
+        }
+
+        assert_eq!(Face::from_look_direction(glam::Vec3::new(0.2, -0.9, 0.4)), Face::Bottom);
+        assert_eq!(Face::from_look_direction(glam::Vec3::new(-0.7, 0.1, 0.6)), Face::Left);
+        assert_eq!(Face::from_look_direction(glam::Vec3::new(0.1, 0.3, -0.5)), Face::Back);
+    }
 }
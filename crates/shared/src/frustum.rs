@@ -54,6 +54,20 @@ impl FrustumCulling {
         }
     }
 
+    /// Builds a frustum from a combined projection and view matrix, which
+    /// may use either a perspective or an orthographic projection.
+    #[inline]
+    pub fn from_matrix(projection_view: glam::Mat4) -> Self {
+        let mut frustum = Self::default();
+
+        frustum.update(projection_view);
+
+        frustum
+    }
+
+    /// Extracts the frustum planes from `projection_view` using the
+    /// Gribb-Hartmann method. The projection is expected to map depth to
+    /// `[0, 1]`, like the `directx` projections in glam do.
     #[inline]
     pub fn update(&mut self, projection_view: glam::Mat4) {
         use Plane::{Bottom, Combinations, Far, Left, Near, Right, Top};
@@ -64,7 +78,7 @@ impl FrustumCulling {
         self[Right] = projection.col(3) - projection.col(0);
         self[Bottom] = projection.col(3) + projection.col(1);
         self[Top] = projection.col(3) - projection.col(1);
-        self[Near] = projection.col(3) + projection.col(2);
+        self[Near] = projection.col(2);
         self[Far] = projection.col(3) - projection.col(2);
 
         let crosses: [glam::Vec3; Combinations as usize] = [
@@ -159,3 +173,37 @@ impl Frustum for FrustumCulling {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Frustum, FrustumCulling};
+
+    fn is_point_visible(frustum: &FrustumCulling, point: glam::Vec3) -> bool {
+        frustum.is_box_visible(point - 0.01, point + 0.01)
+    }
+
+    #[test]
+    fn test_perspective_from_matrix() {
+        let projection = glam::camera::rh::proj::directx::perspective(70f32.to_radians(), 16.0 / 9.0, 0.5, 100.0);
+        let view = glam::camera::rh::view::look_at_mat4(glam::Vec3::ZERO, glam::Vec3::NEG_Z, glam::Vec3::Y);
+        let frustum = FrustumCulling::from_matrix(projection * view);
+
+        assert!(is_point_visible(&frustum, glam::Vec3::new(0.0, 0.0, -10.0)));
+        assert!(!is_point_visible(&frustum, glam::Vec3::new(0.0, 0.0, 10.0)));
+        assert!(!is_point_visible(&frustum, glam::Vec3::new(0.0, 0.0, -0.3)));
+        assert!(!is_point_visible(&frustum, glam::Vec3::new(0.0, 0.0, -200.0)));
+        assert!(!is_point_visible(&frustum, glam::Vec3::new(50.0, 0.0, -10.0)));
+    }
+
+    #[test]
+    fn test_orthographic_from_matrix() {
+        let projection = glam::camera::rh::proj::directx::orthographic(-8.0, 8.0, -8.0, 8.0, 1.0, 50.0);
+        let view = glam::camera::rh::view::look_at_mat4(glam::Vec3::new(0.0, 40.0, 0.0), glam::Vec3::ZERO, glam::Vec3::NEG_Z);
+        let frustum = FrustumCulling::from_matrix(projection * view);
+
+        assert!(is_point_visible(&frustum, glam::Vec3::new(4.0, 0.0, -4.0)));
+        assert!(!is_point_visible(&frustum, glam::Vec3::new(12.0, 0.0, 0.0)));
+        assert!(!is_point_visible(&frustum, glam::Vec3::new(0.0, 39.5, 0.0)));
+        assert!(!is_point_visible(&frustum, glam::Vec3::new(0.0, -20.0, 0.0)));
+    }
+}
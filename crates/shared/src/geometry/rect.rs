@@ -17,6 +17,16 @@ impl Rect {
         self.origin + self.size / 2.0
     }
 
+    /// Returns the overlapping area of both rectangles, which is empty when
+    /// they don't intersect.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let min = self.origin.max(other.origin);
+        let max = (self.origin + self.size).min(other.origin + other.size);
+
+        Self::new(min, (max - min).max(glam::Vec2::ZERO))
+    }
+
     pub fn to_box2(self) -> Box2 {
         Box2 {
             min: self.origin,
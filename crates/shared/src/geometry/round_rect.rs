@@ -1,3 +1,5 @@
+use core::f32::consts::{FRAC_PI_2, PI};
+
 use super::{Box2, Rect, Thickness};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -51,10 +53,10 @@ impl RRect {
         let center = self.center();
         let pt = pt - center;
         let radius = match pt {
-            pt if pt.x < 0.0 && pt.y < 0.0 => self.corner_radius.left(),
-            pt if pt.x >= 0.0 && pt.y < 0.0 => self.corner_radius.top(),
-            pt if pt.x >= 0.0 && pt.y >= 0.0 => self.corner_radius.right(),
-            pt if pt.x < 0.0 && pt.y >= 0.0 => self.corner_radius.bottom(),
+            pt if pt.x < 0.0 && pt.y < 0.0 => self.corner_radius.top_left(),
+            pt if pt.x >= 0.0 && pt.y < 0.0 => self.corner_radius.top_right(),
+            pt if pt.x >= 0.0 && pt.y >= 0.0 => self.corner_radius.bottom_right(),
+            pt if pt.x < 0.0 && pt.y >= 0.0 => self.corner_radius.bottom_left(),
             _ => 0.0,
         };
 
@@ -63,4 +65,82 @@ impl RRect {
 
         px * px + py * py <= radius * radius
     }
+
+    /// Appends a triangle fan approximating this rectangle to `vertices` and
+    /// `indices`, using `corner_segments` segments per corner. Indices are
+    /// offset by the current vertex count, so any number of rectangles can
+    /// share the same buffers and be drawn at once.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn tessellate(&self, corner_segments: u32, vertices: &mut Vec<glam::Vec2>, indices: &mut Vec<u32>) {
+        let half_size = self.size / 2.0;
+        let center = self.center();
+        let max_radius = half_size.x.min(half_size.y).max(0.0);
+        let segments = corner_segments.max(1);
+        let base = vertices.len() as u32;
+
+        vertices.push(center);
+
+        for (radius, corner, start_angle) in [
+            (self.corner_radius.top_left(), glam::Vec2::NEG_ONE, PI),
+            (self.corner_radius.top_right(), glam::Vec2::new(1.0, -1.0), PI + FRAC_PI_2),
+            (self.corner_radius.bottom_right(), glam::Vec2::ONE, 0.0),
+            (self.corner_radius.bottom_left(), glam::Vec2::new(-1.0, 1.0), FRAC_PI_2),
+        ] {
+            let radius = radius.clamp(0.0, max_radius);
+            let arc_center = center + corner * (half_size - radius);
+
+            for i in 0..=segments {
+                let angle = (i as f32 / segments as f32).mul_add(FRAC_PI_2, start_angle);
+
+                vertices.push(arc_center + glam::Vec2::from_angle(angle) * radius);
+            }
+        }
+
+        let perimeter = (segments + 1) * 4;
+
+        for i in 0..perimeter {
+            indices.extend([base, base + 1 + i, base + 1 + (i + 1) % perimeter]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RRect, Thickness};
+
+    #[test]
+    fn test_tessellate_shares_buffers() {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        RRect::new(glam::Vec2::ZERO, glam::Vec2::splat(10.0), Thickness::all(2.0)).tessellate(4, &mut vertices, &mut indices);
+
+        let first_vertices = vertices.len();
+        let first_indices = indices.len();
+
+        RRect::new(glam::Vec2::new(20.0, 0.0), glam::Vec2::splat(10.0), Thickness::all(4.0)).tessellate(4, &mut vertices, &mut indices);
+
+        assert_eq!(vertices.len(), first_vertices * 2);
+        assert_eq!(indices.len(), first_indices * 2);
+        assert!(indices[..first_indices].iter().all(|&index| (index as usize) < first_vertices));
+        assert!(
+            indices[first_indices..]
+                .iter()
+                .all(|&index| (first_vertices..vertices.len()).contains(&(index as usize)))
+        );
+    }
+
+    #[test]
+    fn test_tessellate_stays_in_bounds() {
+        let rect = RRect::new(glam::Vec2::new(5.0, 5.0), glam::Vec2::new(20.0, 10.0), Thickness::all(100.0));
+        let mut vertices = Vec::new();
+
+        rect.tessellate(8, &mut vertices, &mut Vec::new());
+
+        assert!(
+            vertices
+                .iter()
+                .all(|vertex| vertex.cmpge(rect.origin - 0.001).all() && vertex.cmple(rect.origin + rect.size + 0.001).all())
+        );
+    }
 }
@@ -1,8 +1,22 @@
 use crate::Color;
 
 pub trait Lerp {
+    /// Interpolates between `self` and `end` by `x`.
+    ///
+    /// `x` is not clamped, so values outside of `[0, 1]` extrapolate past the
+    /// endpoints. Use [`Lerp::lerp_clamped`] when that is undesirable.
     #[must_use]
     fn lerp(&self, end: &Self, x: f32) -> Self;
+
+    /// Same as [`Lerp::lerp`], but clamps `x` into `[0, 1]` first.
+    #[must_use]
+    #[inline]
+    fn lerp_clamped(&self, end: &Self, x: f32) -> Self
+    where
+        Self: Sized,
+    {
+        self.lerp(end, x.clamp(0.0, 1.0))
+    }
 }
 
 impl Lerp for f32 {
@@ -23,3 +37,45 @@ impl Lerp for Color {
         )
     }
 }
+
+#[cfg(feature = "geometry")]
+impl Lerp for glam::Vec2 {
+    #[inline]
+    fn lerp(&self, end: &Self, x: f32) -> Self {
+        *self + (*end - *self) * x
+    }
+}
+
+#[cfg(feature = "geometry")]
+impl Lerp for glam::Vec3 {
+    #[inline]
+    fn lerp(&self, end: &Self, x: f32) -> Self {
+        *self + (*end - *self) * x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Lerp;
+
+    #[test]
+    fn test_lerp_extrapolates() {
+        assert!((0f32.lerp(&10.0, 1.5) - 15.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_lerp_clamped() {
+        assert!((0f32.lerp_clamped(&10.0, 1.5) - 10.0).abs() < f32::EPSILON);
+        assert!(0f32.lerp_clamped(&10.0, -0.5).abs() < f32::EPSILON);
+    }
+
+    #[cfg(feature = "geometry")]
+    #[test]
+    fn test_lerp_vectors() {
+        assert_eq!(Lerp::lerp(&glam::Vec2::ZERO, &glam::Vec2::new(100.0, -20.0), 0.25), glam::Vec2::new(25.0, -5.0));
+        assert_eq!(
+            Lerp::lerp(&glam::Vec3::ONE, &glam::Vec3::new(3.0, 5.0, 1.0), 0.5),
+            glam::Vec3::new(2.0, 3.0, 1.0)
+        );
+    }
+}
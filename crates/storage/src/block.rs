@@ -3,10 +3,42 @@ use std::{fs, path::Path};
 use ahash::HashMap;
 use mavelin_io::{BlockModel, ColorConfig, TexturePath, TextureRef};
 use mavelin_shared::Color;
-use mavelin_world::Biome;
+use mavelin_world::{Biome, ChunkAccess};
 
 use crate::{LoadingError, LoadingResult, Mappings, ModelLoadingError, texture::TextureStorage};
 
+/// Family of sounds played when a block is stepped on, placed or broken.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundGroup {
+    #[default]
+    Stone,
+    Dirt,
+    Grass,
+    Sand,
+    Wood,
+    Glass,
+    Snow,
+    Liquid,
+    None,
+}
+
+impl SoundGroup {
+    /// Name used to look up the group's sounds, [`None`] for silent blocks.
+    pub const fn name(self) -> Option<&'static str> {
+        match self {
+            Self::Stone => Some("stone"),
+            Self::Dirt => Some("dirt"),
+            Self::Grass => Some("grass"),
+            Self::Sand => Some("sand"),
+            Self::Wood => Some("wood"),
+            Self::Glass => Some("glass"),
+            Self::Snow => Some("snow"),
+            Self::Liquid => Some("liquid"),
+            Self::None => None,
+        }
+    }
+}
+
 pub trait Block: Send + Sync {
     fn id(&self) -> &'static str;
 
@@ -42,6 +74,29 @@ pub trait Block: Send + Sync {
     fn selectable(&self) -> bool {
         true
     }
+
+    /// Whether entities stand on and collide with this block.
+    fn is_solid(&self) -> bool {
+        self.collidable()
+    }
+
+    /// Whether blocks behind this one can be seen through it.
+    fn is_transparent(&self) -> bool {
+        !self.blocks_light()
+    }
+
+    /// Relative time it takes to break this block, `0.0` breaks instantly.
+    fn hardness(&self) -> f32 {
+        1.0
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        SoundGroup::Stone
+    }
+
+    /// Called when a scheduled or random tick lands on this block.
+    #[allow(unused_variables)]
+    fn on_tick(&self, world: &mut dyn ChunkAccess, position: glam::IVec3) {}
 }
 
 #[derive(Debug, Clone)]
@@ -56,6 +111,10 @@ pub struct BlockData {
     pub tint_color: Option<Color>,
     pub collidable: bool,
     pub selectable: bool,
+    pub solid: bool,
+    pub transparent: bool,
+    pub hardness: f32,
+    pub sound_group: SoundGroup,
 }
 
 impl Block for BlockData {
@@ -94,6 +153,22 @@ impl Block for BlockData {
     fn selectable(&self) -> bool {
         self.selectable
     }
+
+    fn is_solid(&self) -> bool {
+        self.solid
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    fn hardness(&self) -> f32 {
+        self.hardness
+    }
+
+    fn sound_group(&self) -> SoundGroup {
+        self.sound_group
+    }
 }
 
 pub struct BlockStorage {
@@ -1,11 +1,15 @@
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
 
 use ahash::HashMap;
 use mavelin_io::{BlockFace, Faces, JsonError, TexturePath, TextureRef};
 use mavelin_physics::Aabb;
 use mavelin_shared::{Axis, Face};
+use tracing::{info, warn};
 
-use crate::{LoadingError, LoadingResult, Mappings, block::BlockStorage, texture::TextureStorage};
+use crate::{LoadingError, LoadingResult, Mappings, block::BlockStorage, modified_time, texture::TextureStorage};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Corner {
@@ -97,22 +101,27 @@ const fn as_vertex_corners(face: Face) -> [Corner; 4] {
     ]
 }
 
+/// Element rotation as a matrix, the point it rotates around and a scale.
+pub type ElementRotation = (glam::Mat4, glam::Vec3, glam::Vec3);
+
+fn rotate_vertex(vertex: glam::Vec3, rotation: Option<&ElementRotation>) -> glam::Vec3 {
+    if let Some((matrix, origin, scale)) = rotation {
+        let point = matrix.transform_point3(vertex - origin);
+
+        glam::Vec3::new(point.x * scale.x, point.y * scale.y, point.z * scale.z) + origin
+    } else {
+        vertex
+    }
+}
+
 impl FaceData {
-    pub fn new(face: Face, aabb: Aabb, uv: FaceUV, rotation: Option<&(glam::Mat4, glam::Vec3, glam::Vec3)>) -> Self {
+    pub fn new(face: Face, aabb: Aabb, uv: FaceUV, rotation: Option<&ElementRotation>) -> Self {
         let mut vertices = face.as_vertices();
 
         let aabb_size = aabb.size().as_vec3();
 
         for vertex in &mut vertices {
-            let vert = aabb.min.as_vec3() + *vertex * aabb_size;
-
-            *vertex = if let Some((matrix, origin, scale)) = rotation {
-                let point = matrix.transform_point3(vert - origin);
-
-                glam::Vec3::new(point.x * scale.x, point.y * scale.y, point.z * scale.z) + origin
-            } else {
-                vert
-            };
+            *vertex = rotate_vertex(aabb.min.as_vec3() + *vertex * aabb_size, rotation);
         }
 
         // match self {
@@ -140,6 +149,36 @@ impl FaceData {
     }
 }
 
+/// Frames of an animated face texture, stacked vertically in the atlas.
+/// Faces are meshed with the UVs of the first frame (see
+/// [`Self::frame_origin`]), renderers copy the current frame over it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureAnimation {
+    /// Position of the whole strip in the atlas, in pixels. The atlas stores
+    /// textures flipped vertically, so the strip's last frame is the one at
+    /// this position and its first frame is at the bottom.
+    pub origin: glam::UVec2,
+    /// Size of a single frame, in pixels.
+    pub frame_size: glam::UVec2,
+    pub frames: u32,
+    pub frame_duration: Duration,
+}
+
+impl TextureAnimation {
+    /// Index of the frame shown after `elapsed` time, looping over all frames.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn frame_at(&self, elapsed: Duration) -> u32 {
+        let frame = elapsed.as_millis() / self.frame_duration.as_millis().max(1);
+
+        (frame % u128::from(self.frames)) as u32
+    }
+
+    /// Position of the `frame`-th frame in the atlas, in pixels.
+    pub const fn frame_origin(&self, frame: u32) -> glam::UVec2 {
+        glam::UVec2::new(self.origin.x, self.origin.y + (self.frames - 1 - frame) * self.frame_size.y)
+    }
+}
+
 #[derive(Debug)]
 pub struct BlockModelFace {
     pub texture_id: usize,
@@ -148,19 +187,38 @@ pub struct BlockModelFace {
     pub tint: bool,
     pub uv: FaceUV,
     pub is_opaque: bool,
+    pub animation: Option<TextureAnimation>,
 }
 
 impl BlockModelFace {
+    #[allow(clippy::cast_precision_loss)]
     fn new(
         texture_storage: &TextureStorage,
         textures: &HashMap<String, TextureRef>,
         aabb: Aabb,
-        rotation: Option<&(glam::Mat4, glam::Vec3, glam::Vec3)>,
+        rotation: Option<&ElementRotation>,
         data: &BlockFace,
         face: Face,
     ) -> Self {
         let texture = get_texture(textures, &data.texture).unwrap();
-        let (offset, scale, alpha) = texture_storage.get_texture(texture.1.file_stem().unwrap().to_string_lossy()).unwrap();
+        let name = texture.1.file_stem().unwrap().to_string_lossy();
+        let (mut offset, mut scale, alpha) = texture_storage.get_texture(&name).unwrap();
+        let animation = data.animation.and_then(|animation| {
+            let (origin, size) = texture_storage.get_texture_rect(&name)?;
+            let frames = size.y / size.x.max(1);
+
+            (frames > 1).then(|| TextureAnimation {
+                origin,
+                frame_size: glam::UVec2::splat(size.x),
+                frames,
+                frame_duration: Duration::from_millis(animation.frame_duration.into()),
+            })
+        });
+
+        if let Some(animation) = animation {
+            scale.y /= animation.frames as f32;
+            offset.y = scale.y.mul_add((animation.frames - 1) as f32, offset.y);
+        }
 
         let uv = if let Some([start, end]) = data.uv {
             FaceUV {
@@ -180,6 +238,7 @@ impl BlockModelFace {
             uv,
             tint: data.tint,
             is_opaque: alpha == 255,
+            animation,
         }
     }
 }
@@ -187,9 +246,68 @@ impl BlockModelFace {
 #[derive(Debug)]
 pub struct BlockModelElement {
     pub cube: Aabb,
+    pub rotation: Option<ElementRotation>,
     pub faces: Vec<BlockModelFace>,
 }
 
+impl BlockModelElement {
+    /// Pairs of [`Self::corners`] indices forming the edges of the cuboid.
+    const EDGES: [[usize; 2]; 12] = [[0, 1], [2, 3], [4, 5], [6, 7], [0, 2], [1, 3], [4, 6], [5, 7], [0, 4], [1, 5], [2, 6], [3, 7]];
+
+    /// Corners of the element cuboid, rotated the same way as its faces.
+    pub fn corners(&self) -> [glam::Vec3; 8] {
+        let (min, max) = (self.cube.min.as_vec3(), self.cube.max.as_vec3());
+
+        std::array::from_fn(|index| {
+            let corner = glam::Vec3::new(
+                if index & 1 == 0 { min.x } else { max.x },
+                if index & 2 == 0 { min.y } else { max.y },
+                if index & 4 == 0 { min.z } else { max.z },
+            );
+
+            rotate_vertex(corner, self.rotation.as_ref())
+        })
+    }
+
+    /// The twelve edges of the element cuboid, rotated the same way as its
+    /// faces, so they line up with the rendered element.
+    pub fn edges(&self) -> impl Iterator<Item = [glam::Vec3; 2]> {
+        let corners = self.corners();
+
+        Self::EDGES.into_iter().map(move |[start, end]| [corners[start], corners[end]])
+    }
+
+    /// Same as [`Self::edges`], but skips edges whose two adjacent faces both
+    /// face away from `eye`, so an outline drawn without depth testing doesn't
+    /// show the hidden back edges. `eye` is in the same space as the element.
+    pub fn visible_edges(&self, eye: glam::Vec3) -> impl Iterator<Item = [glam::Vec3; 2]> {
+        let corners = self.corners();
+        let faces_eye = move |corner: glam::Vec3, axis: usize, positive: bool| {
+            let mut normal = glam::Vec3::ZERO;
+
+            normal[axis] = if positive { 1.0 } else { -1.0 };
+
+            if let Some((matrix, ..)) = &self.rotation {
+                normal = matrix.transform_vector3(normal);
+            }
+
+            normal.dot(eye - corner) > 0.0
+        };
+
+        Self::EDGES.into_iter().filter_map(move |[start, end]| {
+            // Both corners share the bits of the two axes the edge doesn't run
+            // along, and those bits pick its two adjacent faces.
+            let along = (start ^ end).trailing_zeros() as usize;
+            let corner = corners[start];
+            let visible = (0..3)
+                .filter(|&axis| axis != along)
+                .any(|axis| faces_eye(corner, axis, start & (1 << axis) != 0));
+
+            visible.then_some([corners[start], corners[end]])
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct BakedBlockModel {
     pub name: String,
@@ -197,15 +315,17 @@ pub struct BakedBlockModel {
     pub ambient_occlusion: bool,
     pub elements: Vec<BlockModelElement>,
     pub is_opaque: bool,
+    /// Whether the model fully covers each of its sides, indexed by
+    /// [`Face::normal_index`].
+    pub occluded_faces: [bool; 6],
 }
 
 impl BakedBlockModel {
-    pub fn is_opaque(&self, opposite_face: usize) -> bool {
-        self.is_opaque
-            && self
-                .elements
-                .iter()
-                .any(|element| element.faces.get(opposite_face).as_ref().is_some_and(|face| face.is_opaque))
+    /// Whether the model fully covers its `face` side (a
+    /// [`Face::normal_index`]), hiding the neighbouring block's face there.
+    #[inline]
+    pub const fn occludes(&self, face: usize) -> bool {
+        self.occluded_faces[face]
     }
 }
 
@@ -214,6 +334,9 @@ const ERROR: [f32; 3] = [0.00001; 3];
 #[derive(Debug, Default)]
 pub struct BakedBlockModelStorage {
     models: Vec<BakedBlockModel>,
+    /// File and modification time every model was baked from, indexed like
+    /// `models`.
+    sources: Vec<(PathBuf, Option<SystemTime>)>,
 }
 
 fn get_texture<T: AsRef<str>>(textures: &HashMap<String, TextureRef>, name: T) -> Option<&TexturePath> {
@@ -243,6 +366,21 @@ impl BakedBlockModelStorage {
         unsafe { self.models.get_unchecked(value) }
     }
 
+    /// Every distinct texture animation used by the loaded models.
+    pub fn texture_animations(&self) -> Vec<TextureAnimation> {
+        let mut animations: Vec<TextureAnimation> = Vec::new();
+
+        for face in self.models.iter().flat_map(|model| &model.elements).flat_map(|element| &element.faces) {
+            if let Some(animation) = face.animation
+                && !animations.iter().any(|known| known.origin == animation.origin)
+            {
+                animations.push(animation);
+            }
+        }
+
+        animations
+    }
+
     /// # Errors
     ///
     /// An error will be returned if the passed path does not contain a filename
@@ -250,7 +388,47 @@ impl BakedBlockModelStorage {
     /// [`BlockManager::load`]).
     pub fn load<P: AsRef<Path>>(&mut self, textures: &mut TextureStorage, root: &Mappings, path: P) -> LoadingResult<usize> {
         let path = path.as_ref();
+        let model = Self::bake(textures, root, path)?;
+        let index = self.models.len();
+
+        self.models.push(model);
+        self.sources.push((path.to_path_buf(), modified_time(path)));
+
+        Ok(index)
+    }
 
+    /// Re-bakes models whose files changed since they were loaded and returns
+    /// their indices.
+    ///
+    /// Only the model's own file is watched, not its parents. A model that
+    /// fails to load keeps its previous version until the file changes again.
+    pub fn reload_changed(&mut self, textures: &mut TextureStorage, root: &Mappings) -> Vec<usize> {
+        let mut reloaded = Vec::new();
+
+        for (index, (path, modified)) in self.sources.iter_mut().enumerate() {
+            let current = modified_time(path);
+
+            if current == *modified {
+                continue;
+            }
+
+            *modified = current;
+
+            match Self::bake(textures, root, &*path) {
+                Ok(model) => {
+                    info!(target: "model-loader", "Reloaded model at {}", path.display());
+
+                    self.models[index] = model;
+                    reloaded.push(index);
+                }
+                Err(error) => warn!(target: "model-loader", "Failed to reload model at {}, keeping the old one: {error:?}", path.display()),
+            }
+        }
+
+        reloaded
+    }
+
+    fn bake(textures: &mut TextureStorage, root: &Mappings, path: &Path) -> LoadingResult<BakedBlockModel> {
         // println!(
         //     "[{:18}] Loading model at {}",
         //     "INFO/ModelLoader".bright_green(),
@@ -306,6 +484,7 @@ impl BakedBlockModelStorage {
                             face_map
                         }
                     },
+                    rotation,
                 }
             })
             .collect();
@@ -314,16 +493,189 @@ impl BakedBlockModelStorage {
             .iter()
             .any(|element| (element.cube.size().as_vec3() - glam::Vec3::ONE).abs().to_array() < ERROR);
 
-        let index = self.models.len();
+        let occluded_faces = std::array::from_fn(|side| {
+            is_opaque
+                && elements
+                    .iter()
+                    .flat_map(|element| &element.faces)
+                    .any(|face| face.is_opaque && face.face_data.face.normal_index() == side)
+        });
 
-        self.models.push(BakedBlockModel {
+        Ok(BakedBlockModel {
             name: name.to_string(),
             ambient_occlusion: block.ambient_occlusion.unwrap_or(true),
             elements,
             bounding_box: bounding_box.unwrap_or(const { Aabb::new(glam::DVec3::ZERO, glam::DVec3::ONE) }),
             is_opaque,
-        });
+            occluded_faces,
+        })
+    }
+}
 
-        Ok(index)
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        time::{Duration, SystemTime},
+    };
+
+    use ahash::HashMap;
+    use mavelin_physics::Aabb;
+    use mavelin_shared::Face;
+
+    use crate::{BakedBlockModel, BakedBlockModelStorage, BlockModelElement, ResourceStorage, TextureStorage};
+
+    /// The per-face scan meshing did before [`BakedBlockModel::occludes`] was
+    /// precomputed.
+    fn scan_occludes(model: &BakedBlockModel, face: usize) -> bool {
+        model.is_opaque
+            && model
+                .elements
+                .iter()
+                .any(|element| element.faces.get(face).as_ref().is_some_and(|face| face.is_opaque))
+    }
+
+    #[test]
+    fn test_occludes_matches_scan() {
+        let mut storage = ResourceStorage::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../../resources"));
+        let root = storage.mappings["game"].clone();
+
+        for name in ["dirt", "grass_block", "stone", "green_glass_block", "torch"] {
+            let path = root.join("models").join(name).with_extension("json");
+            let index = storage.models.load(&mut storage.textures, &storage.mappings, path).unwrap();
+            let model = storage.models.get_unchecked(index);
+
+            for face in Face::ALL {
+                assert_eq!(
+                    model.occludes(face.normal_index()),
+                    scan_occludes(model, face.normal_index()),
+                    "{name} {face:?}"
+                );
+            }
+        }
+
+        let dirt = storage.models.get_unchecked(0);
+
+        assert!(Face::ALL.into_iter().all(|face| dirt.occludes(face.normal_index())));
+    }
+
+    #[test]
+    fn test_reload_keeps_model_on_error() {
+        let resources = std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../../resources"));
+        let root = std::env::temp_dir().join(format!("mavelin-model-reload-{}", std::process::id()));
+        let model_path = root.join("models/reloaded.json");
+
+        fs::create_dir_all(root.join("models")).unwrap();
+        fs::create_dir_all(root.join("textures")).unwrap();
+        fs::copy(resources.join("models/cuboid_all_same.json"), root.join("models/cuboid_all_same.json")).unwrap();
+        fs::copy(resources.join("textures/dirt.png"), root.join("textures/dirt.png")).unwrap();
+
+        let write_model = |data: &str, seconds: u64| {
+            fs::write(&model_path, data).unwrap();
+            File::options()
+                .write(true)
+                .open(&model_path)
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                .unwrap();
+        };
+
+        let mappings = HashMap::from_iter([(String::from("game"), root.clone())]);
+        let mut textures = TextureStorage::new();
+        let mut models = BakedBlockModelStorage::default();
+
+        write_model(r##"{ "parent": "game:cuboid_all_same", "textures": { "#all": "game:dirt" } }"##, 1);
+
+        let index = models.load(&mut textures, &mappings, &model_path).unwrap();
+
+        assert!(models.reload_changed(&mut textures, &mappings).is_empty());
+
+        write_model("{ not json", 2);
+
+        assert!(models.reload_changed(&mut textures, &mappings).is_empty());
+        assert_eq!(models.get_unchecked(index).elements.len(), 1);
+        assert!(models.get_unchecked(index).ambient_occlusion);
+
+        write_model(
+            r##"{ "parent": "game:cuboid_all_same", "ambient_occlusion": false, "textures": { "#all": "game:dirt" } }"##,
+            3,
+        );
+
+        assert_eq!(models.reload_changed(&mut textures, &mappings), [index]);
+        assert!(!models.get_unchecked(index).ambient_occlusion);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_visible_edges_skip_back_faces() {
+        let element = BlockModelElement {
+            cube: Aabb::new(glam::DVec3::ZERO, glam::DVec3::ONE),
+            rotation: None,
+            faces: Vec::new(),
+        };
+
+        assert_eq!(element.visible_edges(glam::Vec3::new(0.5, 0.5, 5.0)).count(), 4);
+        assert_eq!(element.visible_edges(glam::Vec3::splat(5.0)).count(), 9);
+        assert!(
+            element
+                .visible_edges(glam::Vec3::new(0.5, 0.5, 5.0))
+                .all(|[start, end]| (start.z - 1.0).abs() < f32::EPSILON && (end.z - 1.0).abs() < f32::EPSILON)
+        );
+    }
+
+    #[test]
+    fn test_animated_face() {
+        let root = std::env::temp_dir().join(format!("mavelin-model-animation-{}", std::process::id()));
+        let model_path = root.join("models/animated.json");
+
+        fs::create_dir_all(root.join("models")).unwrap();
+        fs::create_dir_all(root.join("textures")).unwrap();
+        image::RgbaImage::from_fn(16, 64, |_, y| image::Rgba([u8::try_from(y / 16 * 60).unwrap(), 0, 0, 255]))
+            .save(root.join("textures/strip.png"))
+            .unwrap();
+        fs::write(
+            &model_path,
+            r##"{
+                "textures": { "#all": "game:strip" },
+                "elements": [{
+                    "start": [0, 0, 0],
+                    "end": [1, 1, 1],
+                    "all": { "texture": "#all", "animation": { "frame_duration": 100 } }
+                }]
+            }"##,
+        )
+        .unwrap();
+
+        let mappings = HashMap::from_iter([(String::from("game"), root.clone())]);
+        let mut textures = TextureStorage::new();
+        let mut models = BakedBlockModelStorage::default();
+        let index = models.load(&mut textures, &mappings, &model_path).unwrap();
+        let face = &models.get_unchecked(index).elements[0].faces[0];
+        let animation = face.animation.unwrap();
+
+        assert_eq!(animation.frames, 4);
+        assert_eq!(animation.frame_duration, Duration::from_millis(100));
+        assert_eq!(animation.frame_size, glam::UVec2::splat(16));
+        assert!((face.uv.scale.x - face.uv.scale.y).abs() < f32::EPSILON);
+        assert!((face.uv.offset - animation.frame_origin(0).as_vec2() / f32::from(TextureStorage::ATLAS_SIZE)).length() < f32::EPSILON);
+
+        for frame in 0..animation.frames {
+            let origin = animation.frame_origin(frame);
+
+            for y in 0..animation.frame_size.y {
+                assert_eq!(
+                    textures.get_atlas()[(origin.x, origin.y + y)].0,
+                    [u8::try_from(frame * 60).unwrap(), 0, 0, 255],
+                    "frame {frame}"
+                );
+            }
+        }
+
+        assert_eq!(animation.frame_at(Duration::from_millis(250)), 2);
+        assert_eq!(animation.frame_at(Duration::from_millis(450)), 0);
+        assert_eq!(models.texture_animations(), [animation]);
+
+        fs::remove_dir_all(root).unwrap();
     }
 }
@@ -8,6 +8,7 @@ use std::{
     fs,
     ops::Not,
     path::{Path, PathBuf, absolute},
+    time::SystemTime,
 };
 
 use ahash::HashMap;
@@ -16,7 +17,7 @@ pub use mavelin_io::{BiomeColorConfig, ColorConfig};
 use mavelin_world::BlockSource;
 
 pub use self::{
-    block::{Block, BlockData, BlockStorage},
+    block::{Block, BlockData, BlockStorage, SoundGroup},
     block_model::*,
     entity::EntityModelStorage,
     texture::{TextureLoadingError, TextureStorage},
@@ -24,6 +25,10 @@ pub use self::{
 
 pub type LoadingResult<T> = Result<T, LoadingError>;
 
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
 #[derive(Debug)]
 pub enum LoadingError {
     Texture(TextureLoadingError),
@@ -96,6 +101,22 @@ impl ResourceStorage {
         entity_id
     }
 
+    /// Re-reads block models and textures whose files changed on disk, see
+    /// [`BakedBlockModelStorage::reload_changed`] and
+    /// [`TextureStorage::reload_changed`]. Mipmaps are regenerated if anything
+    /// was reloaded. Returns the indices of the reloaded models and whether
+    /// any texture changed.
+    pub fn reload(&mut self) -> (Vec<usize>, bool) {
+        let textures_changed = self.textures.reload_changed() > 0;
+        let models = self.models.reload_changed(&mut self.textures, &self.mappings);
+
+        if textures_changed || !models.is_empty() {
+            self.generate_mipmaps(self.get_mipmaps().len() - 1);
+        }
+
+        (models, textures_changed)
+    }
+
     pub fn generate_mipmaps(&mut self, level: usize) {
         self.textures.generate_mipmaps(level);
     }
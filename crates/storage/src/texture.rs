@@ -1,15 +1,22 @@
 use core::fmt;
-use std::{io, path::Path};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
+use ahash::HashMap;
 use image::RgbaImage;
 use meck::TextureAtlas;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::{LoadingError, LoadingResult};
+use crate::{LoadingError, LoadingResult, modified_time};
 
 pub struct TextureStorage {
     regular_atlas: TextureAtlas<String>,
     lightmap_atlas: TextureAtlas<String>,
+    /// File and modification time every regular texture was loaded from.
+    sources: HashMap<String, (PathBuf, Option<SystemTime>)>,
 }
 
 #[derive(Debug)]
@@ -36,6 +43,7 @@ impl TextureStorage {
         Self {
             regular_atlas: TextureAtlas::new(Self::ATLAS_SIZE.into()).with_mipmaps(4),
             lightmap_atlas: TextureAtlas::new(Self::ATLAS_SIZE.into()).with_mipmaps(4),
+            sources: HashMap::default(),
         }
     }
 
@@ -47,6 +55,11 @@ impl TextureStorage {
         self.regular_atlas.get_texture_uv(name.as_ref())
     }
 
+    /// Position and size of a regular texture in the atlas, in pixels.
+    pub fn get_texture_rect<T: AsRef<str>>(&self, name: T) -> Option<(glam::UVec2, glam::UVec2)> {
+        self.regular_atlas.get_texture_rect(name.as_ref()).map(|(origin, size, _)| (origin, size))
+    }
+
     pub fn get_lightmap<T: AsRef<str>>(&self, name: T) -> Option<(glam::Vec2, glam::Vec2, u8)> {
         self.lightmap_atlas.get_texture_uv(name.as_ref())
     }
@@ -140,6 +153,8 @@ impl TextureStorage {
 
                     info!(target: "texture-loader", width = image.width(), height = image.height(), "Loaded texture at {}", path.display());
 
+                    self.sources.insert(name.clone(), (path.to_path_buf(), modified_time(path)));
+
                     Ok(Some(self.regular_atlas.special_append(name, &image)))
                 }
                 Err(error) => Err(LoadingError::Texture(TextureLoadingError::Decode(error))),
@@ -149,6 +164,40 @@ impl TextureStorage {
     }
 }
 
+impl TextureStorage {
+    /// Re-reads regular textures whose files changed since they were loaded
+    /// and overwrites them in the atlas. Returns how many were replaced.
+    ///
+    /// A texture that fails to decode or changed size keeps its old pixels,
+    /// resizing would require repacking the whole atlas. Mipmaps have to be
+    /// regenerated afterwards.
+    pub fn reload_changed(&mut self) -> usize {
+        let mut reloaded = 0;
+
+        for (name, (path, modified)) in &mut self.sources {
+            let current = modified_time(path);
+
+            if current == *modified {
+                continue;
+            }
+
+            *modified = current;
+
+            match image::open(&*path) {
+                Ok(image) if self.regular_atlas.replace(name.as_str(), &image.to_rgba8()) => {
+                    info!(target: "texture-loader", "Reloaded texture at {}", path.display());
+
+                    reloaded += 1;
+                }
+                Ok(_) => warn!(target: "texture-loader", "Texture at {} changed size, keeping the old one", path.display()),
+                Err(error) => warn!(target: "texture-loader", "Failed to reload texture at {}: {error}", path.display()),
+            }
+        }
+
+        reloaded
+    }
+}
+
 impl Default for TextureStorage {
     fn default() -> Self {
         Self::new()
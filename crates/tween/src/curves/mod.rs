@@ -31,6 +31,69 @@ trait ParametricCurve<T> {
 
 pub trait ICurve {
     fn transform(&self, t: f32) -> f32;
+
+    /// Returns `count` evenly spaced `(t, transform(t))` pairs covering
+    /// `0.0..=1.0`, e.g. for plotting the curve.
+    fn sample(&self, count: usize) -> Vec<(f32, f32)> {
+        match count {
+            0 => Vec::new(),
+            1 => vec![(0.0, self.transform(0.0))],
+            count => (0..count)
+                .map(|i| {
+                    let t = i as f32 / (count - 1) as f32;
+
+                    (t, self.transform(t))
+                })
+                .collect(),
+        }
+    }
+
+    /// Finds `t` such that `transform(t) == y`.
+    ///
+    /// The inverse is only well-defined for monotonic curves. For others
+    /// (bounce, elastic, back easings) this returns the first root found
+    /// scanning from `0.0`, or the closest sample if `y` is never reached.
+    fn inverse(&self, y: f32) -> f32 {
+        const SCAN_STEPS: usize = 64;
+        const REFINE_STEPS: usize = 32;
+
+        let mut closest = (0.0, f32::INFINITY);
+        let mut start = (0.0, self.transform(0.0) - y);
+
+        for i in 1..=SCAN_STEPS {
+            let t = i as f32 / SCAN_STEPS as f32;
+            let end = (t, self.transform(t) - y);
+
+            if start.1.abs() < closest.1 {
+                closest = (start.0, start.1.abs());
+            }
+
+            if start.1 == 0.0 {
+                return start.0;
+            }
+
+            if start.1.signum() != end.1.signum() {
+                let (mut low, mut high) = (start, end);
+
+                for _ in 0..REFINE_STEPS {
+                    let t = f32::midpoint(low.0, high.0);
+                    let value = self.transform(t) - y;
+
+                    if value.signum() == low.1.signum() {
+                        low = (t, value);
+                    } else {
+                        high = (t, value);
+                    }
+                }
+
+                return if low.1.abs() < high.1.abs() { low.0 } else { high.0 };
+            }
+
+            start = end;
+        }
+
+        if start.1.abs() < closest.1 { start.0 } else { closest.0 }
+    }
 }
 
 impl<T: ParametricCurve<f32>> ICurve for T {
@@ -393,6 +393,120 @@ impl<T: Lerp> Animation for KeyframeAnimation<T> {
     }
 }
 
+/// Tweens through a list of waypoints, each segment with its own duration and
+/// curve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathTween<T: Lerp> {
+    /// Current value.
+    value: T,
+    /// Values the path passes through, starting with the initial one.
+    waypoints: Vec<T>,
+    /// Duration (in milliseconds) and curve of the segment leading to each
+    /// waypoint after the first one.
+    segments: Vec<(u64, Curve)>,
+    /// Time elapsed since start of the path (in milliseconds), across all
+    /// repetitions.
+    elapsed: u64,
+    repeat: RepeatMode,
+}
+
+impl<T: Lerp + Clone> PathTween<T> {
+    #[must_use]
+    pub fn new(start: T) -> Self {
+        Self {
+            value: start.clone(),
+            waypoints: vec![start],
+            segments: Vec::new(),
+            elapsed: 0,
+            repeat: RepeatMode::Once,
+        }
+    }
+
+    /// Appends a waypoint reached `duration` milliseconds after the previous
+    /// one.
+    #[must_use]
+    pub fn then(mut self, waypoint: T, duration: u64, curve: Curve) -> Self {
+        self.waypoints.push(waypoint);
+        self.segments.push((duration, curve));
+
+        self
+    }
+
+    /// Sets how many times the whole path is walked.
+    #[must_use]
+    pub fn with_repeat_mode(mut self, mode: RepeatMode) -> Self {
+        self.repeat = mode;
+
+        self
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Time it takes to walk the path once (in milliseconds).
+    pub fn get_duration(&self) -> u64 {
+        self.segments.iter().map(|&(duration, _)| duration).sum()
+    }
+
+    /// Whether the path went past its last waypoint for the last time.
+    pub fn is_finished(&self) -> bool {
+        match self.repeat {
+            RepeatMode::Once => self.elapsed >= self.get_duration(),
+            RepeatMode::Times(n) => self.elapsed >= self.get_duration() * u64::from(n),
+            RepeatMode::Infinite => self.get_duration() == 0,
+        }
+    }
+
+    fn advance_value(&mut self) {
+        if self.is_finished() {
+            self.value = self.waypoints[self.waypoints.len() - 1].clone();
+
+            return;
+        }
+
+        let mut time = self.elapsed % self.get_duration();
+
+        for (i, &(duration, curve)) in self.segments.iter().enumerate() {
+            if time < duration {
+                self.value = self.waypoints[i].lerp(&self.waypoints[i + 1], curve.transform(time as f32 / duration as f32));
+
+                return;
+            }
+
+            time -= duration;
+        }
+    }
+}
+
+impl<T: Lerp + Copy> PathTween<T> {
+    pub fn get_copy(&self) -> T {
+        self.value
+    }
+}
+
+impl<T: Lerp + Clone> Animation for PathTween<T> {
+    type Item = T;
+
+    fn set_elapsed(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed.as_millis() as u64;
+        self.advance_value();
+    }
+
+    fn advance(&mut self, delta: Duration) -> AnimationResult {
+        if self.is_finished() {
+            self.advance_value();
+
+            AnimationResult::Finished
+        } else {
+            self.elapsed = self.elapsed.saturating_add(delta.as_millis() as u64);
+            self.advance_value();
+
+            AnimationResult::InProgress
+        }
+    }
+}
+
 impl_tuple_anim![A => 0, B => 1];
 impl_tuple_anim![A => 0, B => 1, C => 2];
 impl_tuple_anim![A => 0, B => 1, C => 2, D => 3];
@@ -402,7 +516,24 @@ impl_tuple_anim![A => 0, B => 1, C => 2, D => 3, E => 4];
 mod tests {
     use std::time::Duration;
 
-    use crate::{Animation, Curve, KeyframeAnimation, Tween};
+    use crate::{Animation, AnimationResult, Curve, ICurve, KeyframeAnimation, PathTween, RepeatMode, Tween};
+
+    #[test]
+    fn test_curve_inverse() {
+        for (t, y) in Curve::EASE_IN_OUT.sample(21) {
+            assert!((Curve::EASE_IN_OUT.inverse(y) - t).abs() < 0.01, "inverse({y}) should be close to {t}");
+        }
+
+        assert!((Curve::LINEAR.inverse(0.25) - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_curve_sample() {
+        let samples = Curve::LINEAR.sample(5);
+
+        assert_eq!(samples, [(0.0, 0.0), (0.25, 0.25), (0.5, 0.5), (0.75, 0.75), (1.0, 1.0)]);
+        assert!(Curve::LINEAR.sample(0).is_empty());
+    }
 
     #[test]
     fn test_keyframes() {
@@ -422,6 +553,35 @@ mod tests {
         println!("{}ms: {}, {}", text_animation.elapsed, text_animation.value, animation.value);
     }
 
+    #[test]
+    fn test_path_tween() {
+        let mut path = PathTween::new(0.0).then(10.0, 100, Curve::LINEAR).then(20.0, 200, Curve::LINEAR);
+
+        path.advance(Duration::from_millis(50));
+        assert!((path.get_copy() - 5.0).abs() < 1e-4);
+
+        path.advance(Duration::from_millis(150));
+        assert!((path.get_copy() - 15.0).abs() < 1e-4);
+
+        assert_eq!(path.advance(Duration::from_millis(150)), AnimationResult::InProgress);
+        assert!(path.is_finished());
+        assert!((path.get_copy() - 20.0).abs() < 1e-4);
+        assert_eq!(path.advance(Duration::from_millis(10)), AnimationResult::Finished);
+    }
+
+    #[test]
+    fn test_path_tween_repeat() {
+        let mut path = PathTween::new(0.0).then(10.0, 100, Curve::LINEAR).with_repeat_mode(RepeatMode::Times(2));
+
+        path.advance(Duration::from_millis(130));
+        assert!(!path.is_finished());
+        assert!((path.get_copy() - 3.0).abs() < 1e-4);
+
+        path.advance(Duration::from_millis(70));
+        assert!(path.is_finished());
+        assert!((path.get_copy() - 10.0).abs() < 1e-4);
+    }
+
     #[test]
     fn test_tween() {
         let mut tween = Tween::new(0.0, 1.0, 400);
@@ -75,7 +75,7 @@ impl<'a, C: ChunkAccess> BfsLight<'a, C> {
                         let neighbour_light_level = chunk.get_block_light(local_position);
 
                         if neighbour_light_level != 0 && neighbour_light_level < node_light_level {
-                            chunk.dirty = true;
+                            chunk.mark_dirty(local_position.y);
                             chunk.set_block_light(local_position, 0);
 
                             self.block_removing_queue
@@ -107,7 +107,7 @@ impl<'a, C: ChunkAccess> BfsLight<'a, C> {
                     if !block_source.blocks_light(block.id) && chunk.get_block_light(position) + 2 <= light_level {
                         let light_level = light_level - 1 - block_source.light_consumption(block.id);
 
-                        chunk.dirty = true;
+                        chunk.mark_dirty(position.y);
                         chunk.set_block_light_by_idx(subchunk, index, light_level);
 
                         self.block_addition_queue.push_back((LightNode(position, chunk.origin), light_level));
@@ -131,7 +131,7 @@ impl<'a, C: ChunkAccess> BfsLight<'a, C> {
                         let neighbour_light_level = chunk.get_sky_light(local_position);
 
                         if (face == Face::Bottom && node_light_level == 15) || (neighbour_light_level != 0 && neighbour_light_level < node_light_level) {
-                            chunk.dirty = true;
+                            chunk.mark_dirty(local_position.y);
                             chunk.set_sky_light(local_position, 0);
 
                             self.sky_removing_queue
@@ -167,7 +167,7 @@ impl<'a, C: ChunkAccess> BfsLight<'a, C> {
                     if !blocks_light && chunk.get_sky_light_by_idx(subchunk, index) + 2 <= light_level {
                         let light_level = light_level - consumes - u8::from(!skip_decrease);
 
-                        chunk.dirty = true;
+                        chunk.mark_dirty(position.y);
                         chunk.set_sky_light_by_idx(subchunk, index, light_level);
 
                         self.sky_addition_queue.push_back((LightNode(position, chunk.origin), light_level));
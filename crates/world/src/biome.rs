@@ -20,6 +20,21 @@ pub enum Biome {
 }
 
 impl Biome {
+    pub const ALL: [Self; 13] = [
+        Self::Rainforest,
+        Self::Swampland,
+        Self::SeasonalForest,
+        Self::Forest,
+        Self::Savanna,
+        Self::Shrubland,
+        Self::Taiga,
+        Self::Desert,
+        Self::Plains,
+        Self::IceDesert,
+        Self::Tundra,
+        Self::Hell,
+        Self::Sky,
+    ];
     const LOOKUP: [Self; 64 * 64] = const {
         let mut table = [const { Self::Sky }; 64 * 64];
         let mut i = 0;
@@ -251,6 +251,12 @@ impl SubChunk {
         }
     }
 
+    /// Returns `true` if the subchunk contains only air.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.palette.iter().all(SubChunkBlockState::is_air)
+    }
+
     #[inline]
     pub const fn iter(&self, subchunk_idx: usize) -> SubChunkIter<'_> {
         SubChunkIter::new(self, subchunk_idx)
@@ -267,17 +273,22 @@ pub struct Chunk {
     pub biomes: [Biome; SUBCHUNK_SIZE * SUBCHUNK_SIZE],
     /// Array of chunk vertical sections
     pub subchunks: Box<[SubChunk; SUBCHUNK_COUNT]>,
-    pub dirty: bool,
+    /// Bit mask of subchunks whose meshes are out of date.
+    pub dirty_subchunks: u16,
 }
 
+const _: () = assert!(SUBCHUNK_COUNT <= u16::BITS as usize);
+
 impl Chunk {
+    pub const ALL_SUBCHUNKS: u16 = u16::MAX >> (u16::BITS as usize - SUBCHUNK_COUNT);
+
     #[inline]
     pub fn empty() -> Self {
         Self {
             origin: glam::IVec2::ZERO,
             biomes: [Biome::Sky; SUBCHUNK_SIZE * SUBCHUNK_SIZE],
             subchunks: SubChunk::empty_full_height(),
-            dirty: true,
+            dirty_subchunks: Self::ALL_SUBCHUNKS,
         }
     }
 
@@ -454,6 +465,40 @@ impl Chunk {
         self.origin.x == (position.x >> 4) && self.origin.y == (position.z >> 4) && (0..SUBCHUNK_COUNT_I32).contains(&(position.y >> 4))
     }
 
+    #[inline]
+    pub const fn is_dirty(&self) -> bool {
+        self.dirty_subchunks != 0
+    }
+
+    /// Marks the subchunk containing `y` as dirty. Blocks on a subchunk's top
+    /// or bottom layer also dirty the adjacent subchunk, since its faces and
+    /// lighting depend on them.
+    #[inline]
+    pub const fn mark_dirty(&mut self, y: usize) {
+        let [subchunk, local_y] = Self::get_subchunk_index(y);
+
+        if subchunk < SUBCHUNK_COUNT {
+            self.dirty_subchunks |= 1 << subchunk;
+        }
+
+        if local_y == 0 && subchunk > 0 {
+            self.dirty_subchunks |= 1 << (subchunk - 1);
+        } else if local_y == SUBCHUNK_SIZE - 1 && subchunk + 1 < SUBCHUNK_COUNT {
+            self.dirty_subchunks |= 1 << (subchunk + 1);
+        }
+    }
+
+    #[inline]
+    pub const fn mark_all_dirty(&mut self) {
+        self.dirty_subchunks = Self::ALL_SUBCHUNKS;
+    }
+
+    /// Returns the dirty subchunk mask and clears it.
+    #[inline]
+    pub const fn take_dirty_subchunks(&mut self) -> u16 {
+        std::mem::replace(&mut self.dirty_subchunks, 0)
+    }
+
     #[inline]
     pub fn set_block(&mut self, position: glam::USizeVec3, block: SubChunkBlockState) {
         if self.contains_local_position(position) {
@@ -471,6 +516,8 @@ impl Chunk {
 
             subchunk.set_index_unchecked(SubChunk::index_of(position.with_y(y)), index);
         }
+
+        self.mark_dirty(position.y);
     }
 
     #[inline]
@@ -665,6 +712,58 @@ impl Chunk {
     pub const fn face_iter(&self, face: Face) -> ChunkFaceIter<'_> {
         ChunkFaceIter::new(self, face)
     }
+
+    /// Iterates over all non-air blocks with their chunk-local positions,
+    /// skipping subchunks that contain only air.
+    #[inline]
+    pub fn iter_solid_blocks(&self) -> impl Iterator<Item = (glam::USizeVec3, &SubChunkBlockState)> {
+        self.subchunks
+            .iter()
+            .enumerate()
+            .filter(|(_, subchunk)| !subchunk.is_empty())
+            .flat_map(|(subchunk_idx, subchunk)| subchunk.iter(subchunk_idx))
+            .filter_map(|(position, block)| block.map(|block| (position, block)))
+    }
+
+    /// Same as [`Chunk::iter_solid_blocks`], but yields world positions.
+    #[inline]
+    pub fn iter_world_blocks(&self) -> impl Iterator<Item = (glam::IVec3, &SubChunkBlockState)> {
+        self.iter_solid_blocks().map(|(position, block)| (self.to_world(position), block))
+    }
+
+    /// Counts how many blocks of each id the chunk contains, air included.
+    /// Block states that differ only in properties are counted together.
+    pub fn block_histogram(&self) -> HashMap<u32, u32> {
+        const SUBCHUNK_VOLUME: usize = SUBCHUNK_SIZE * SUBCHUNK_SIZE * SUBCHUNK_SIZE;
+
+        let mut histogram = HashMap::default();
+
+        for subchunk in self.subchunks.iter() {
+            let mut counts = vec![0u32; subchunk.palette.len()];
+
+            match &subchunk.data {
+                PaletteData::Single => counts[0] = SUBCHUNK_VOLUME as u32,
+                PaletteData::Linear(data) => {
+                    for index in 0..SUBCHUNK_VOLUME {
+                        counts[data.get(index)] += 1;
+                    }
+                }
+            }
+
+            for (block, count) in subchunk.palette.iter().zip(counts) {
+                if count > 0 {
+                    *histogram.entry(block.id).or_insert(0) += count;
+                }
+            }
+        }
+
+        histogram
+    }
+
+    /// Returns the number of blocks in the chunk that are not air.
+    pub fn non_air_count(&self) -> u32 {
+        self.block_histogram().into_iter().filter(|&(id, _)| id != 0).map(|(_, count)| count).sum()
+    }
 }
 
 impl<'a> IntoIterator for &'a Chunk {
@@ -833,3 +932,82 @@ impl<'a> Iterator for SubChunkIter<'a> {
         Some((chunk_local_position, if block_state.is_air() { None } else { Some(block_state) }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{CHUNK_HEIGHT, Chunk, SUBCHUNK_SIZE, SubChunkBlockState};
+
+    #[test]
+    fn test_iter_solid_blocks() {
+        let mut chunk = Chunk::new(glam::IVec2::new(-2, 3));
+
+        chunk.set_block(glam::USizeVec3::new(1, 2, 3), SubChunkBlockState::new(1));
+        chunk.set_block(glam::USizeVec3::new(15, 200, 0), SubChunkBlockState::new(2));
+        chunk.set_block(glam::USizeVec3::new(4, 4, 4), SubChunkBlockState::new(3));
+        chunk.set_block(glam::USizeVec3::new(4, 4, 4), SubChunkBlockState::air());
+
+        let blocks = chunk.iter_solid_blocks().map(|(position, block)| (position, block.id)).collect::<Vec<_>>();
+
+        assert_eq!(blocks, [(glam::USizeVec3::new(1, 2, 3), 1), (glam::USizeVec3::new(15, 200, 0), 2)]);
+        assert_eq!(chunk.iter().filter(|(_, block)| !block.is_air()).count(), blocks.len());
+    }
+
+    #[test]
+    fn test_dirty_subchunks() {
+        let mut chunk = Chunk::new(glam::IVec2::ZERO);
+
+        assert_eq!(chunk.take_dirty_subchunks(), Chunk::ALL_SUBCHUNKS);
+        assert!(!chunk.is_dirty());
+
+        chunk.set_block(glam::USizeVec3::new(0, 40, 0), SubChunkBlockState::new(1));
+
+        assert_eq!(chunk.take_dirty_subchunks(), 0b100);
+
+        chunk.set_block(glam::USizeVec3::new(0, 32, 0), SubChunkBlockState::new(1));
+
+        assert_eq!(chunk.take_dirty_subchunks(), 0b110);
+
+        chunk.set_block(glam::USizeVec3::new(0, 47, 0), SubChunkBlockState::new(1));
+
+        assert_eq!(chunk.take_dirty_subchunks(), 0b1100);
+
+        chunk.mark_dirty(0);
+        chunk.mark_dirty(255);
+
+        assert_eq!(chunk.take_dirty_subchunks(), 0b1000_0000_0000_0001);
+    }
+
+    #[test]
+    fn test_iter_world_blocks() {
+        let mut chunk = Chunk::new(glam::IVec2::new(-2, 3));
+
+        chunk.set_block(glam::USizeVec3::new(1, 2, 3), SubChunkBlockState::new(1));
+
+        let blocks = chunk.iter_world_blocks().map(|(position, block)| (position, block.id)).collect::<Vec<_>>();
+
+        assert_eq!(blocks, [(glam::IVec3::new(-31, 2, 51), 1)]);
+    }
+
+    #[test]
+    fn test_block_histogram() {
+        let mut chunk = Chunk::new(glam::IVec2::ZERO);
+
+        chunk.set_block(glam::USizeVec3::new(1, 2, 3), SubChunkBlockState::new(1));
+        chunk.set_block(glam::USizeVec3::new(1, 200, 3), SubChunkBlockState::new(1));
+
+        let mut lit = SubChunkBlockState::new(1);
+
+        lit.set_bool("lit", true);
+
+        chunk.set_block(glam::USizeVec3::new(2, 2, 3), lit);
+        chunk.set_block(glam::USizeVec3::new(4, 4, 4), SubChunkBlockState::new(2));
+
+        let histogram = chunk.block_histogram();
+
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram[&1], 3);
+        assert_eq!(histogram[&2], 1);
+        assert_eq!(histogram[&0], (SUBCHUNK_SIZE * SUBCHUNK_SIZE * CHUNK_HEIGHT) as u32 - 4);
+        assert_eq!(chunk.non_air_count(), 4);
+    }
+}
@@ -1,12 +1,16 @@
 use std::{
+    collections::VecDeque,
     ops::{Index, IndexMut},
     sync::Arc,
 };
 
-use ahash::HashMap;
+use ahash::{HashMap, HashSet, HashSetExt};
 use mavelin_shared::Face;
 
-use crate::{BfsLight, Biome, BlockSource, Chunk, LightNode, SUBCHUNK_COUNT_I32, SUBCHUNK_SIZE, SUBCHUNK_SIZE_I32, chunk::SubChunkBlockState};
+use crate::{
+    BfsLight, Biome, BlockSource, CHUNK_HEIGHT, CHUNK_HEIGHT_I32, Chunk, LightNode, SUBCHUNK_COUNT_I32, SUBCHUNK_SIZE, SUBCHUNK_SIZE_I32,
+    chunk::SubChunkBlockState,
+};
 
 pub trait ChunkCache {
     fn all(&self) -> impl Iterator<Item = Chunk>;
@@ -419,8 +423,15 @@ impl<C: ChunkCache> ChunkManager<C> {
         glam::IVec2::new(position.x >> 4, position.z >> 4)
     }
 
+    /// Converts a world position into a position inside its chunk, [`None`]
+    /// if that chunk isn't loaded or the position is above or below the
+    /// world.
     pub fn to_chunk_local(&self, position: glam::IVec3) -> Option<glam::USizeVec3> {
-        self.get_chunk(Self::to_local(position)).map(|_| Chunk::to_local(position))
+        if (0..CHUNK_HEIGHT_I32).contains(&position.y) {
+            self.get_chunk(Self::to_local(position)).map(|_| Chunk::to_local(position))
+        } else {
+            None
+        }
     }
 
     pub fn get_biome(&self, position: glam::IVec3) -> Option<Biome> {
@@ -434,6 +445,29 @@ impl<C: ChunkCache> ChunkManager<C> {
         }
     }
 
+    /// Marks the subchunks of neighbouring chunks that touch the block at
+    /// `position`, including diagonal ones, so that their faces and ambient
+    /// occlusion are remeshed after the block changes.
+    pub fn mark_neighbours_dirty(&mut self, position: glam::IVec3) {
+        let chunk_position = Self::to_local(position);
+        let local = Chunk::to_local(position);
+
+        for normal in Face::NORMALS.into_iter().chain([
+            glam::IVec3::NEG_ONE,
+            glam::IVec3::NEG_ONE.with_x(1),
+            glam::IVec3::ONE.with_x(-1),
+            glam::IVec3::ONE,
+        ]) {
+            let chunk = Self::to_local(position + normal);
+
+            if chunk != chunk_position
+                && let Some(chunk) = self.get_chunk_mut(chunk)
+            {
+                chunk.mark_dirty(local.y);
+            }
+        }
+    }
+
     pub fn remove_block<T: BlockSource>(&mut self, position: glam::IVec3, block_source: &T) {
         let chunk_position = Self::to_local(position);
 
@@ -441,32 +475,8 @@ impl<C: ChunkCache> ChunkManager<C> {
             let local = Chunk::to_local(position);
 
             chunk.set_block(local, SubChunkBlockState::air());
-            chunk.dirty = true;
-
-            for normal in Face::NORMALS {
-                let chunk = Self::to_local(position + normal);
 
-                if chunk != chunk_position
-                    && let Some(chunk) = self.get_chunk_mut(chunk)
-                {
-                    chunk.dirty = true;
-                }
-            }
-
-            for normal in [
-                glam::IVec3::NEG_ONE,
-                glam::IVec3::NEG_ONE.with_x(1),
-                glam::IVec3::ONE.with_x(-1),
-                glam::IVec3::ONE,
-            ] {
-                let chunk = Self::to_local(position + normal);
-
-                if chunk != chunk_position
-                    && let Some(chunk) = self.get_chunk_mut(chunk)
-                {
-                    chunk.dirty = true;
-                }
-            }
+            self.mark_neighbours_dirty(position);
 
             let mut bfs_light = BfsLight::new(self);
 
@@ -499,6 +509,85 @@ impl<C: ChunkCache> ChunkManager<C> {
         }
     }
 
+    /// Sets every block between `min` and `max` (inclusive) to `block`, then
+    /// relights the region in a single pass instead of once per block.
+    ///
+    /// Blocks outside of loaded chunks or the world height are skipped. Light
+    /// emitted by `block` itself is not propagated. Returns the origins of the
+    /// chunks that were edited or got new dirty subchunks from relighting.
+    pub fn fill_region<T: BlockSource>(&mut self, min: glam::IVec3, max: glam::IVec3, block: &SubChunkBlockState, block_source: &T) -> HashSet<glam::IVec2> {
+        let (min, max) = (min.min(max), min.max(max));
+        let (min, max) = (min.with_y(min.y.max(0)), max.with_y(max.y.min(CHUNK_HEIGHT_I32 - 1)));
+        let dirty_before: HashMap<glam::IVec2, u16> = self.chunks.iter().map(|(origin, chunk)| (*origin, chunk.dirty_subchunks)).collect();
+        let mut changed = HashSet::new();
+        let mut filled = Vec::new();
+
+        for x in min.x..=max.x {
+            for z in min.z..=max.z {
+                let origin = Self::to_local(glam::IVec3::new(x, 0, z));
+
+                let Some(chunk) = self.get_chunk_mut(origin) else {
+                    continue;
+                };
+
+                changed.insert(origin);
+
+                for y in min.y..=max.y {
+                    let local = Chunk::to_local(glam::IVec3::new(x, y, z));
+
+                    chunk.set_block(local, block.clone());
+                    filled.push(LightNode(local, origin));
+                }
+
+                if x == min.x || x == max.x || z == min.z || z == max.z {
+                    for y in min.y..=max.y {
+                        self.mark_neighbours_dirty(glam::IVec3::new(x, y, z));
+                    }
+                }
+            }
+        }
+
+        let mut bfs_light = BfsLight::new(self);
+
+        for &node in &filled {
+            bfs_light.remove_block(node);
+            bfs_light.remove_sky(node);
+        }
+
+        bfs_light.calculate_block_light(block_source);
+        bfs_light.calculate_sky_light(block_source);
+
+        if block.is_air() {
+            let top = max.y as usize;
+
+            for &LightNode(local, origin) in filled.iter().filter(|node| node.0.y == top) {
+                let chunk = &bfs_light.chunk_manager[origin];
+
+                if top + 1 < CHUNK_HEIGHT && chunk.get_sky_light(local.with_y(top + 1)) != 15 {
+                    continue;
+                }
+
+                for y in (0..=top).rev() {
+                    if bfs_light.chunk_manager[origin].get_block(local.with_y(y)).is_some_and(|block| !block.is_air()) {
+                        break;
+                    }
+
+                    bfs_light.add_sky_custom(LightNode(local.with_y(y), origin), 15);
+                }
+            }
+
+            bfs_light.calculate_sky_light(block_source);
+        }
+
+        changed.extend(self.chunks.iter().filter_map(|(origin, chunk)| {
+            let before = dirty_before.get(origin).copied().unwrap_or_default();
+
+            (chunk.dirty_subchunks & !before != 0).then_some(*origin)
+        }));
+
+        changed
+    }
+
     pub fn set_block_light(&mut self, position: glam::IVec3, light_level: u8) {
         if let Some(chunk) = self.get_chunk_mut(Self::to_local(position)) {
             chunk.set_block_light(Chunk::to_local(position), light_level);
@@ -521,6 +610,52 @@ impl<C: ChunkCache> ChunkManager<C> {
         self.get_chunk(Self::to_local(position)).is_some_and(|chunk| chunk.check_for_block(position))
     }
 
+    /// Returns the position of the topmost non-air block in the column at
+    /// `x`/`z`, or [`None`] if the column is empty or its chunk isn't loaded.
+    pub fn highest_block(&self, x: i32, z: i32) -> Option<glam::IVec3> {
+        let position = glam::IVec3::new(x, 0, z);
+        let chunk = self.get_chunk(Self::to_local(position))?;
+        let local = Chunk::to_local(position);
+
+        (0..CHUNK_HEIGHT)
+            .rev()
+            .find(|&y| chunk.check_for_local_block(glam::USizeVec3 { y, ..local }))
+            .map(|y| glam::IVec3::new(x, y as i32, z))
+    }
+
+    /// Collects up to `max` blocks connected to `start` through their faces
+    /// whose id satisfies `matches`, nearest first. The fill doesn't enter
+    /// unloaded chunks, and returns nothing if `start` itself doesn't match.
+    pub fn flood_fill(&self, start: glam::IVec3, matches: impl Fn(u32) -> bool, max: usize) -> Vec<glam::IVec3> {
+        let is_match = |position| self.get_block(position).is_some_and(|block| matches(block.id));
+        let mut filled = Vec::new();
+
+        if max == 0 || !is_match(start) {
+            return filled;
+        }
+
+        let mut visited = HashSet::from_iter([start]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(position) = queue.pop_front() {
+            filled.push(position);
+
+            if filled.len() == max {
+                break;
+            }
+
+            for normal in Face::NORMALS {
+                let neighbour = position + normal;
+
+                if visited.insert(neighbour) && is_match(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        filled
+    }
+
     pub fn contains_chunk(&self, origin: &glam::IVec2) -> bool {
         self.chunks.contains_key(origin)
     }
@@ -704,3 +839,224 @@ impl<C: ChunkCache> IndexMut<glam::IVec2> for ChunkManager<C> {
         Arc::make_mut(self.chunks.get_mut(&index).unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{BlockSource, CHUNK_HEIGHT, Chunk, ChunkAccess, ChunkManager, ChunkStage, SUBCHUNK_SIZE, SubChunkBlockState};
+
+    fn surface_height(x: usize, z: usize) -> usize {
+        60 + (x * 3 + z * 5) % 9
+    }
+
+    fn surface_chunk(origin: glam::IVec2) -> Chunk {
+        let mut chunk = Chunk::new(origin);
+
+        for z in 0..SUBCHUNK_SIZE {
+            for x in 0..SUBCHUNK_SIZE {
+                for y in 0..=surface_height(x, z) {
+                    chunk.set_block(glam::USizeVec3::new(x, y, z), SubChunkBlockState::new(1));
+                }
+            }
+        }
+
+        chunk
+    }
+
+    struct OpaqueBlocks;
+
+    impl BlockSource for OpaqueBlocks {
+        fn get_block_id(&self, _: &str) -> u32 {
+            1
+        }
+
+        fn blocks_light(&self, block: u32) -> bool {
+            block != 0
+        }
+
+        fn light_consumption(&self, _: u32) -> u8 {
+            0
+        }
+    }
+
+    #[test]
+    fn test_fill_region_across_chunks() {
+        let mut chunk_manager = ChunkManager::default();
+
+        for x in -1..=1 {
+            for z in -1..=1 {
+                chunk_manager.push(Chunk::new(glam::IVec2::new(x, z)), ChunkStage::Populated);
+            }
+        }
+
+        chunk_manager.generate_sky_lights(&OpaqueBlocks);
+
+        for chunk in chunk_manager.chunks_mut() {
+            chunk.take_dirty_subchunks();
+        }
+
+        let min = glam::IVec3::new(-8, 100, -8);
+        let max = min + 15;
+        let dirty = chunk_manager.fill_region(min, max, &SubChunkBlockState::new(1), &OpaqueBlocks);
+
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                for z in min.z..=max.z {
+                    assert_eq!(chunk_manager.get_block(glam::IVec3::new(x, y, z)).map(|block| block.id), Some(1));
+                }
+            }
+        }
+
+        for origin in [glam::IVec2::new(-1, -1), glam::IVec2::new(-1, 0), glam::IVec2::new(0, -1), glam::IVec2::ZERO] {
+            assert!(dirty.contains(&origin), "{origin} should need a remesh");
+        }
+
+        assert!(chunk_manager.get_sky_light(glam::IVec3::new(0, 99, 0)) < 15);
+        assert_eq!(chunk_manager.get_sky_light(glam::IVec3::new(0, 116, 0)), 15);
+
+        chunk_manager.fill_region(min, max, &SubChunkBlockState::air(), &OpaqueBlocks);
+
+        assert_eq!(chunk_manager.get_sky_light(glam::IVec3::new(0, 99, 0)), 15);
+    }
+
+    #[test]
+    fn test_flood_fill_cube() {
+        let mut chunk_manager = ChunkManager::default();
+
+        for origin in [glam::IVec2::NEG_X, glam::IVec2::ZERO] {
+            chunk_manager.push(surface_chunk(origin), ChunkStage::Populated);
+        }
+
+        let min = glam::IVec3::new(-2, 30, 4);
+        let max = min + 3;
+
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                for z in min.z..=max.z {
+                    chunk_manager.set_block(glam::IVec3::new(x, y, z), SubChunkBlockState::new(2));
+                }
+            }
+        }
+
+        let mut filled = chunk_manager.flood_fill(min + 1, |id| id == 2, 1000);
+
+        filled.sort_by_key(glam::IVec3::to_array);
+
+        let mut expected = Vec::new();
+
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                for z in min.z..=max.z {
+                    expected.push(glam::IVec3::new(x, y, z));
+                }
+            }
+        }
+
+        assert_eq!(filled, expected);
+        assert_eq!(chunk_manager.flood_fill(min, |id| id == 2, 10).len(), 10);
+        assert!(chunk_manager.flood_fill(min - 1, |id| id == 2, 1000).is_empty());
+    }
+
+    #[test]
+    fn test_flood_fill_stops_at_unloaded_chunks() {
+        let mut chunk_manager = ChunkManager::default();
+
+        chunk_manager.push(Chunk::new(glam::IVec2::ZERO), ChunkStage::Populated);
+
+        let filled = chunk_manager.flood_fill(glam::IVec3::new(0, 200, 0), |id| id == 0, 100_000);
+
+        assert_eq!(filled.len(), SUBCHUNK_SIZE * SUBCHUNK_SIZE * CHUNK_HEIGHT);
+    }
+
+    #[test]
+    fn test_highest_block() {
+        let mut chunk_manager = ChunkManager::default();
+
+        chunk_manager.push(surface_chunk(glam::IVec2::ZERO), ChunkStage::Populated);
+        chunk_manager.push(surface_chunk(glam::IVec2::NEG_ONE), ChunkStage::Populated);
+
+        assert_eq!(chunk_manager.highest_block(0, 0), Some(glam::IVec3::new(0, 60, 0)));
+        assert_eq!(chunk_manager.highest_block(7, 3), Some(glam::IVec3::new(7, surface_height(7, 3) as i32, 3)));
+        assert_eq!(
+            chunk_manager.highest_block(-1, -1),
+            Some(glam::IVec3::new(-1, surface_height(15, 15) as i32, -1))
+        );
+        assert_eq!(chunk_manager.highest_block(16, 0), None);
+    }
+
+    #[test]
+    fn test_highest_block_empty_column() {
+        let mut chunk = surface_chunk(glam::IVec2::ZERO);
+
+        for y in 0..CHUNK_HEIGHT {
+            chunk.set_block(glam::USizeVec3::new(4, y, 4), SubChunkBlockState::air());
+        }
+
+        chunk.set_block(glam::USizeVec3::new(5, 255, 5), SubChunkBlockState::new(1));
+
+        let mut chunk_manager = ChunkManager::default();
+
+        chunk_manager.push(chunk, ChunkStage::Populated);
+
+        assert_eq!(chunk_manager.highest_block(4, 4), None);
+        assert_eq!(chunk_manager.highest_block(5, 5), Some(glam::IVec3::new(5, 255, 5)));
+    }
+
+    #[test]
+    fn test_to_local_round_trip() {
+        for x in -40..40 {
+            for z in -40..40 {
+                for y in [0, 15, 16, CHUNK_HEIGHT as i32 - 1] {
+                    let position = glam::IVec3::new(x, y, z);
+                    let origin = ChunkManager::<()>::to_local(position);
+                    let local = Chunk::to_local(position);
+
+                    assert!(local.x < SUBCHUNK_SIZE && local.z < SUBCHUNK_SIZE, "{position} -> {local}");
+                    assert_eq!(Chunk::to_world_pos(origin, local), position);
+                    assert_eq!(Chunk::to_origin_and_local(position), (origin, local));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_local_negative_coordinates() {
+        assert_eq!(ChunkManager::<()>::to_local(glam::IVec3::new(-1, 0, -16)), glam::IVec2::new(-1, -1));
+        assert_eq!(ChunkManager::<()>::to_local(glam::IVec3::new(-17, 0, 15)), glam::IVec2::new(-2, 0));
+        assert_eq!(Chunk::to_local(glam::IVec3::new(-1, 0, -16)), glam::USizeVec3::new(15, 0, 0));
+        assert_eq!(Chunk::to_local(glam::IVec3::new(-17, 0, -15)), glam::USizeVec3::new(15, 0, 1));
+    }
+
+    #[test]
+    fn test_to_chunk_local() {
+        let mut chunk_manager = ChunkManager::default();
+
+        chunk_manager.push(Chunk::new(glam::IVec2::NEG_ONE), ChunkStage::Populated);
+
+        assert_eq!(
+            chunk_manager.to_chunk_local(glam::IVec3::new(-1, 64, -16)),
+            Some(glam::USizeVec3::new(15, 64, 0))
+        );
+        assert_eq!(chunk_manager.to_chunk_local(glam::IVec3::new(0, 64, -16)), None);
+        assert_eq!(chunk_manager.to_chunk_local(glam::IVec3::new(-1, -1, -1)), None);
+        assert_eq!(chunk_manager.to_chunk_local(glam::IVec3::new(-1, CHUNK_HEIGHT as i32, -1)), None);
+    }
+
+    #[test]
+    fn test_mark_neighbours_dirty() {
+        let mut chunk_manager = ChunkManager::default();
+
+        for origin in [glam::IVec2::ZERO, glam::IVec2::NEG_X, glam::IVec2::NEG_ONE, glam::IVec2::X] {
+            let mut chunk = Chunk::new(origin);
+
+            chunk.take_dirty_subchunks();
+            chunk_manager.push(chunk, ChunkStage::Populated);
+        }
+
+        chunk_manager.mark_neighbours_dirty(glam::IVec3::new(0, 40, 0));
+
+        assert_eq!(chunk_manager[glam::IVec2::NEG_X].dirty_subchunks, 1 << (40 / SUBCHUNK_SIZE));
+        assert_eq!(chunk_manager[glam::IVec2::NEG_ONE].dirty_subchunks, 1 << (40 / SUBCHUNK_SIZE));
+        assert_eq!(chunk_manager[glam::IVec2::ZERO].dirty_subchunks, 0);
+        assert_eq!(chunk_manager[glam::IVec2::X].dirty_subchunks, 0);
+    }
+}
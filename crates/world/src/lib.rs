@@ -11,6 +11,8 @@ mod bfs_light;
 mod biome;
 mod chunk;
 mod chunk_manager;
+mod save;
+mod tick;
 
 use core::fmt;
 
@@ -24,6 +26,8 @@ pub use self::{
         SUBCHUNK_COUNT_U16, SUBCHUNK_SIZE, SUBCHUNK_SIZE_F32, SUBCHUNK_SIZE_F64, SUBCHUNK_SIZE_I32, SUBCHUNK_SIZE_U16, SubChunk, SubChunkBlockState,
     },
     chunk_manager::{ChunkAccess, ChunkCache, ChunkManager, ChunkStage, LocalChunkManager},
+    save::{WORLD_FORMAT_VERSION, WorldHeader},
+    tick::BlockTicks,
 };
 
 pub trait BlockSource {
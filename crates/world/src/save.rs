@@ -0,0 +1,379 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::Path,
+};
+
+use ahash::HashMap;
+use tracing::warn;
+
+use crate::{
+    Biome, Chunk, ChunkCache, ChunkManager, ChunkStage, PropertyValue, SUBCHUNK_SIZE, SubChunk, SubChunkBlockState,
+    chunk::{PackedArray, PaletteData},
+};
+
+const WORLD_MAGIC: [u8; 4] = *b"MVWD";
+const SUBCHUNK_VOLUME: usize = SUBCHUNK_SIZE * SUBCHUNK_SIZE * SUBCHUNK_SIZE;
+
+/// Version of the world file layout written by [`ChunkManager::save_world`].
+///
+/// Bump it whenever the chunk encoding changes and keep reading older
+/// versions in [`Chunk::deserialize`].
+pub const WORLD_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldHeader {
+    pub version: u32,
+    pub seed: u32,
+    pub chunk_count: u32,
+}
+
+fn invalid_data<T: Into<Box<dyn std::error::Error + Send + Sync>>>(error: T) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn read_array<const N: usize>(data: &mut &[u8]) -> io::Result<[u8; N]> {
+    let mut buffer = [0; N];
+
+    data.read_exact(&mut buffer)?;
+
+    Ok(buffer)
+}
+
+fn read_u16(data: &mut &[u8]) -> io::Result<u16> {
+    read_array(data).map(u16::from_be_bytes)
+}
+
+fn read_u32(data: &mut &[u8]) -> io::Result<u32> {
+    read_array(data).map(u32::from_be_bytes)
+}
+
+fn write_string(data: &mut Vec<u8>, value: &str) {
+    data.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    data.extend_from_slice(value.as_bytes());
+}
+
+fn read_string(data: &mut &[u8]) -> io::Result<String> {
+    let mut buffer = vec![0; read_u16(data)? as usize];
+
+    data.read_exact(&mut buffer)?;
+
+    String::from_utf8(buffer).map_err(invalid_data)
+}
+
+fn write_state(data: &mut Vec<u8>, state: &SubChunkBlockState) {
+    data.extend_from_slice(&state.id.to_be_bytes());
+    data.extend_from_slice(&(state.properties.len() as u16).to_be_bytes());
+
+    for (name, value) in &state.properties {
+        write_string(data, name);
+
+        match value {
+            PropertyValue::Number(value) => {
+                data.push(0);
+                data.extend_from_slice(&value.to_be_bytes());
+            }
+            PropertyValue::Float(value) => {
+                data.push(1);
+                data.extend_from_slice(&value.to_be_bytes());
+            }
+            PropertyValue::String(value) => {
+                data.push(2);
+                write_string(data, value);
+            }
+            PropertyValue::Boolean(value) => data.extend([3, u8::from(*value)]),
+        }
+    }
+}
+
+fn read_state(data: &mut &[u8]) -> io::Result<SubChunkBlockState> {
+    let id = read_u32(data)?;
+    let count = read_u16(data)?;
+    let mut properties = HashMap::default();
+
+    for _ in 0..count {
+        let name = read_string(data)?;
+        let value = match read_array::<1>(data)? {
+            [0] => PropertyValue::Number(i64::from_be_bytes(read_array(data)?)),
+            [1] => PropertyValue::Float(f32::from_be_bytes(read_array(data)?)),
+            [2] => PropertyValue::String(read_string(data)?),
+            [3] => PropertyValue::Boolean(read_array::<1>(data)? != [0]),
+            [tag] => return Err(invalid_data(format!("unknown property tag {tag}"))),
+        };
+
+        properties.insert(name, value);
+    }
+
+    Ok(SubChunkBlockState { id, properties })
+}
+
+/// Writes `values` as `(run length, value)` pairs.
+fn write_runs<I: IntoIterator<Item = u16>>(data: &mut Vec<u8>, values: I) {
+    let mut values = values.into_iter().peekable();
+
+    while let Some(value) = values.next() {
+        let mut length = 1u16;
+
+        while length < u16::MAX && values.next_if_eq(&value).is_some() {
+            length += 1;
+        }
+
+        data.extend_from_slice(&length.to_be_bytes());
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn read_runs(data: &mut &[u8], mut output: impl FnMut(usize, u16) -> io::Result<()>) -> io::Result<()> {
+    let mut index = 0;
+
+    while index < SUBCHUNK_VOLUME {
+        let length = read_u16(data)? as usize;
+        let value = read_u16(data)?;
+
+        if length == 0 || index + length > SUBCHUNK_VOLUME {
+            return Err(invalid_data("run overflows the subchunk"));
+        }
+
+        for index in index..index + length {
+            output(index, value)?;
+        }
+
+        index += length;
+    }
+
+    Ok(())
+}
+
+impl SubChunk {
+    fn serialize(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&(self.palette.len() as u16).to_be_bytes());
+
+        for state in &self.palette {
+            write_state(data, state);
+        }
+
+        if self.palette.len() > 1 {
+            write_runs(data, (0..SUBCHUNK_VOLUME).map(|index| self.get_index_unchecked(index) as u16));
+        }
+
+        write_runs(data, self.light_levels.iter().map(|&light| u16::from(light)));
+    }
+
+    fn deserialize(data: &mut &[u8]) -> io::Result<Self> {
+        let palette_size = read_u16(data)? as usize;
+
+        if palette_size == 0 {
+            return Err(invalid_data("empty subchunk palette"));
+        }
+
+        let palette = (0..palette_size).map(|_| read_state(data)).collect::<io::Result<Vec<_>>>()?;
+        let mut subchunk = Self {
+            palette,
+            data: if palette_size > 1 {
+                PaletteData::Linear(PackedArray::new(palette_size))
+            } else {
+                PaletteData::Single
+            },
+            light_levels: [0; SUBCHUNK_VOLUME],
+        };
+
+        if palette_size > 1 {
+            read_runs(data, |index, value| {
+                if value as usize >= palette_size {
+                    return Err(invalid_data("palette index out of range"));
+                }
+
+                subchunk.set_index_unchecked(index, value as usize);
+
+                Ok(())
+            })?;
+        }
+
+        read_runs(data, |index, value| {
+            subchunk.light_levels[index] = u8::try_from(value).map_err(invalid_data)?;
+
+            Ok(())
+        })?;
+
+        Ok(subchunk)
+    }
+}
+
+impl Chunk {
+    /// Encodes the chunk's blocks, biomes and light levels, run-length
+    /// compressing block indices and light levels of each subchunk.
+    #[must_use]
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+
+        data.extend_from_slice(&self.origin.x.to_be_bytes());
+        data.extend_from_slice(&self.origin.y.to_be_bytes());
+        data.extend(self.biomes.iter().map(|&biome| biome as u8));
+
+        for subchunk in self.subchunks.iter() {
+            subchunk.serialize(&mut data);
+        }
+
+        data
+    }
+
+    /// Decodes a chunk written by [`Chunk::serialize`].
+    pub fn deserialize<T: AsRef<[u8]>>(data: T) -> io::Result<Self> {
+        let mut data = data.as_ref();
+        let mut chunk = Self::empty();
+
+        chunk.origin = glam::IVec2::new(i32::from_be_bytes(read_array(&mut data)?), i32::from_be_bytes(read_array(&mut data)?));
+
+        for biome in &mut chunk.biomes {
+            let [index] = read_array(&mut data)?;
+
+            *biome = *Biome::ALL.get(index as usize).ok_or_else(|| invalid_data(format!("unknown biome {index}")))?;
+        }
+
+        for subchunk in chunk.subchunks.iter_mut() {
+            *subchunk = SubChunk::deserialize(&mut data)?;
+        }
+
+        if data.is_empty() {
+            Ok(chunk)
+        } else {
+            Err(invalid_data("trailing data after chunk"))
+        }
+    }
+}
+
+impl<C: ChunkCache> ChunkManager<C> {
+    /// Writes every loaded chunk into a single file, preceded by a
+    /// [`WorldHeader`].
+    pub fn save_world<P: AsRef<Path>>(&self, path: P, seed: u32) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        writer.write_all(&WORLD_MAGIC)?;
+        writer.write_all(&WORLD_FORMAT_VERSION.to_be_bytes())?;
+        writer.write_all(&seed.to_be_bytes())?;
+        writer.write_all(&(self.chunks.len() as u32).to_be_bytes())?;
+
+        for chunk in self.chunks.values() {
+            let data = chunk.serialize();
+
+            writer.write_all(&(data.len() as u32).to_be_bytes())?;
+            writer.write_all(&data)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Loads chunks saved by [`ChunkManager::save_world`], replacing loaded
+    /// chunks with the same origin. Chunks that fail to decode are skipped
+    /// with a warning instead of failing the whole load.
+    ///
+    /// # Errors
+    ///
+    /// An error will be returned if the file cannot be read, is not a world
+    /// file or was written by a newer format version.
+    pub fn load_world<P: AsRef<Path>>(&mut self, path: P) -> io::Result<WorldHeader> {
+        let data = fs::read(path)?;
+        let mut data = data.as_slice();
+
+        if read_array(&mut data)? != WORLD_MAGIC {
+            return Err(invalid_data("not a world file"));
+        }
+
+        let header = WorldHeader {
+            version: read_u32(&mut data)?,
+            seed: read_u32(&mut data)?,
+            chunk_count: read_u32(&mut data)?,
+        };
+
+        if header.version > WORLD_FORMAT_VERSION {
+            return Err(invalid_data(format!("unsupported world format version {}", header.version)));
+        }
+
+        for index in 0..header.chunk_count {
+            let Some(length) = read_u32(&mut data).ok().map(|length| length as usize).filter(|&length| length <= data.len()) else {
+                warn!(target: "world/save", "World file is truncated after {index} of {} chunks", header.chunk_count);
+
+                break;
+            };
+
+            let (chunk, rest) = data.split_at(length);
+
+            data = rest;
+
+            match Chunk::deserialize(chunk) {
+                Ok(chunk) => self.push(chunk, ChunkStage::Lighted),
+                Err(error) => warn!(target: "world/save", "Skipping corrupt chunk #{index}: {error}"),
+            }
+        }
+
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Biome, Chunk, ChunkManager, SubChunkBlockState, save::WORLD_FORMAT_VERSION};
+
+    fn test_chunk(origin: glam::IVec2) -> Chunk {
+        let mut chunk = Chunk::new(origin);
+        let mut log = SubChunkBlockState::new(7);
+
+        log.set_i64("axis", 2);
+        log.set_bool("natural", true);
+
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in 0..40 {
+                    chunk.set_block(glam::USizeVec3::new(x, y, z), SubChunkBlockState::new(1 + (y as u32 % 3)));
+                }
+            }
+        }
+
+        chunk.set_block(glam::USizeVec3::new(3, 200, 5), log);
+        chunk.set_sky_light(glam::USizeVec3::new(1, 60, 1), 15);
+        chunk.biomes[17] = Biome::Desert;
+
+        chunk
+    }
+
+    #[test]
+    fn test_chunk_roundtrip() {
+        let chunk = test_chunk(glam::IVec2::new(-3, 8));
+        let decoded = Chunk::deserialize(chunk.serialize()).unwrap();
+
+        assert_eq!(decoded, chunk);
+    }
+
+    #[test]
+    fn test_chunk_rejects_truncated_data() {
+        let data = test_chunk(glam::IVec2::ZERO).serialize();
+
+        assert!(Chunk::deserialize(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_world_skips_corrupt_chunks() {
+        let path = std::env::temp_dir().join(format!("mavelin-world-{}.mvw", std::process::id()));
+        let mut chunk_manager = ChunkManager::default();
+
+        chunk_manager.push(test_chunk(glam::IVec2::ZERO), crate::ChunkStage::Meshed);
+        chunk_manager.push(test_chunk(glam::IVec2::X), crate::ChunkStage::Meshed);
+        chunk_manager.save_world(&path, 42).unwrap();
+
+        // Flip the first biome byte of the first chunk record to an invalid value.
+        let mut data = std::fs::read(&path).unwrap();
+
+        data[16 + 4 + 8] = 0xFF;
+        std::fs::write(&path, data).unwrap();
+
+        let mut loaded = ChunkManager::default();
+        let header = loaded.load_world(&path).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(header.version, WORLD_FORMAT_VERSION);
+        assert_eq!(header.seed, 42);
+        assert_eq!(header.chunk_count, 2);
+        assert_eq!(loaded.chunks.len(), 1);
+    }
+}
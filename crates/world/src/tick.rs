@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use mavelin_shared::Random;
+
+use crate::{CHUNK_HEIGHT_I32, Chunk, SUBCHUNK_SIZE_I32};
+
+/// Keeps track of block ticks: ticks scheduled for a specific position after
+/// a delay, and random ticks sampled from every loaded chunk.
+pub struct BlockTicks {
+    current_tick: u64,
+    scheduled: BTreeMap<u64, Vec<glam::IVec3>>,
+    random: Random,
+}
+
+impl BlockTicks {
+    /// How many random ticks every chunk receives per game tick.
+    pub const RANDOM_TICKS_PER_CHUNK: usize = 3;
+
+    pub const fn new(seed: i64) -> Self {
+        Self {
+            current_tick: 0,
+            scheduled: BTreeMap::new(),
+            random: Random::new(seed),
+        }
+    }
+
+    /// Number of times [`Self::advance`] was called.
+    pub const fn current_tick(&self) -> u64 {
+        self.current_tick
+    }
+
+    /// Schedules a tick at `position` to fire `delay_ticks` game ticks from
+    /// now. A delay of zero fires on the next tick, and scheduling the same
+    /// position for the same tick twice only fires it once.
+    pub fn schedule(&mut self, position: glam::IVec3, delay_ticks: u64) {
+        let positions = self.scheduled.entry(self.current_tick + delay_ticks.max(1)).or_default();
+
+        if !positions.contains(&position) {
+            positions.push(position);
+        }
+    }
+
+    /// Number of scheduled ticks that have not fired yet.
+    pub fn pending(&self) -> usize {
+        self.scheduled.values().map(Vec::len).sum()
+    }
+
+    /// Moves to the next game tick and returns the positions whose scheduled
+    /// ticks are due, in the order they were scheduled.
+    pub fn advance(&mut self) -> Vec<glam::IVec3> {
+        self.current_tick += 1;
+
+        let pending = self.scheduled.split_off(&(self.current_tick + 1));
+
+        std::mem::replace(&mut self.scheduled, pending).into_values().flatten().collect()
+    }
+
+    /// Samples [`Self::RANDOM_TICKS_PER_CHUNK`] random block positions in the
+    /// chunk at `origin`.
+    pub fn random_positions(&mut self, origin: glam::IVec2) -> impl Iterator<Item = glam::IVec3> {
+        (0..Self::RANDOM_TICKS_PER_CHUNK).map(move |_| {
+            let local = glam::IVec3::new(
+                self.random.next_i32(SUBCHUNK_SIZE_I32),
+                self.random.next_i32(CHUNK_HEIGHT_I32),
+                self.random.next_i32(SUBCHUNK_SIZE_I32),
+            );
+
+            Chunk::to_world_pos(origin, local.as_usizevec3())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockTicks;
+    use crate::{CHUNK_HEIGHT_I32, SUBCHUNK_SIZE_I32};
+
+    #[test]
+    fn test_scheduled_tick_fires_after_delay() {
+        let mut ticks = BlockTicks::new(0);
+        let position = glam::IVec3::new(4, 64, -2);
+
+        ticks.advance();
+        ticks.schedule(position, 3);
+        ticks.schedule(position, 3);
+
+        assert!(ticks.advance().is_empty());
+        assert!(ticks.advance().is_empty());
+        assert_eq!(ticks.advance(), [position]);
+        assert!(ticks.advance().is_empty());
+        assert_eq!(ticks.pending(), 0);
+    }
+
+    #[test]
+    fn test_random_positions_stay_in_chunk() {
+        let mut ticks = BlockTicks::new(0);
+        let origin = glam::IVec2::new(-3, 5);
+
+        for _ in 0..100 {
+            let positions = ticks.random_positions(origin).collect::<Vec<_>>();
+
+            assert_eq!(positions.len(), BlockTicks::RANDOM_TICKS_PER_CHUNK);
+
+            for position in positions {
+                assert_eq!(position.x.div_euclid(SUBCHUNK_SIZE_I32), origin.x);
+                assert_eq!(position.z.div_euclid(SUBCHUNK_SIZE_I32), origin.y);
+                assert!((0..CHUNK_HEIGHT_I32).contains(&position.y));
+            }
+        }
+    }
+}
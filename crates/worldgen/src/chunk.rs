@@ -476,3 +476,51 @@ impl ChunkGenerator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mavelin_world::{BlockSource, CHUNK_HEIGHT, Chunk, SUBCHUNK_SIZE};
+
+    use super::ChunkGenerator;
+
+    const BLOCKS: &[&str] = &[
+        "game:air",
+        "game:stone",
+        "game:water",
+        "game:ice",
+        "game:sand",
+        "game:dirt",
+        "game:grass_block",
+        "game:snow",
+    ];
+
+    struct NamedBlocks;
+
+    impl BlockSource for NamedBlocks {
+        fn get_block_id(&self, name: &str) -> u32 {
+            BLOCKS.iter().position(|block| *block == name).map_or(BLOCKS.len() as u32, |id| id as u32)
+        }
+
+        fn blocks_light(&self, block: u32) -> bool {
+            block != 0
+        }
+
+        fn light_consumption(&self, _: u32) -> u8 {
+            0
+        }
+    }
+
+    #[test]
+    fn test_generated_chunk_histogram() {
+        let generator = ChunkGenerator::new(0);
+        let mut chunk = Chunk::new(glam::IVec2::new(3, -2));
+
+        generator.generate_unpopulated_chunk_data(&mut chunk, &NamedBlocks);
+
+        let histogram = chunk.block_histogram();
+        let air = histogram.get(&0).copied().unwrap_or_default();
+
+        assert_eq!((air + chunk.non_air_count()) as usize, SUBCHUNK_SIZE * SUBCHUNK_SIZE * CHUNK_HEIGHT);
+        assert!(histogram.get(&1).is_some_and(|&stone| stone > 0));
+    }
+}
@@ -300,31 +300,43 @@ fn vs_main(
 var atlas: texture_2d<f32>;
 @group(0) @binding(2)
 var atlas_sampler: sampler;
+@group(0) @binding(3)
+var sdf_sampler: sampler;
 
+// `r` holds corner radii as (top left, top right, bottom left, bottom right),
+// `p` is relative to the rectangle center with Y pointing down.
 fn sd_round_box(p: vec2<f32>, b: vec2<f32>, r: vec4<f32>) -> f32 {
-    var radii = r;
-    let xy = select(radii.xy, radii.wz, p.x > 0.0);
+    let side = select(r.xz, r.yw, p.x > 0.0);
+    let radius = min(select(side.x, side.y, p.y > 0.0), min(b.x, b.y));
+    let q = abs(p) - b + radius;
 
-    radii.x = xy.x;
-    radii.y = xy.y;
-    radii.x = select(radii.x, radii.y, p.y > 0.0);
-
-    let q = abs(p) - b + r.x;
-
-    return min(max(q.x, q.y), 0.0) + length(max(q, vec2(0.0))) - r.x;
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2(0.0))) - radius;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let d = sd_round_box(in.local_uv, in.half_size, in.radii);
+    // Width of a single pixel in local units, keeps edges one pixel wide
+    // regardless of scale. Derivatives have to be taken in uniform control flow.
+    let w = max(fwidth(d), 0.0001);
+    // Distance fields keep the distance in alpha, with the glyph edge at 0.5.
+    let distance = textureSample(atlas, sdf_sampler, in.local_uv).a;
+    let distance_width = max(fwidth(distance), 0.0001);
+
     if in.mode == 0u {
-        let d = sd_round_box(in.local_uv, in.half_size, in.radii);
-        let a = 1.0 - smoothstep(-0.5, 0.5, d);
+        let a = 1.0 - smoothstep(-0.5 * w, 0.5 * w, d);
 
         return vec4(in.color.rgb, in.color.a * a);
     } else if in.mode == 1u {
         let mask = textureSample(atlas, atlas_sampler, in.local_uv).r;
 
         return vec4(in.color.rgb, in.color.a * mask);
+    } else if in.mode == 3u {
+        return textureSample(atlas, atlas_sampler, in.local_uv) * in.color;
+    } else if in.mode == 4u {
+        let a = smoothstep(0.5 - distance_width, 0.5 + distance_width, distance);
+
+        return vec4(in.color.rgb, in.color.a * a);
     } else {
         return in.color;
     }
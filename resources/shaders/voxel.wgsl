@@ -3,6 +3,7 @@ struct VertexInput {
     @location(1) uv: vec2<f32>,
     @location(2) color: vec4<u32>,
     @location(3) light: u32,
+    @builtin(vertex_index) vertex_index: u32,
 }
 
 struct VertexOutput {
@@ -12,6 +13,7 @@ struct VertexOutput {
     @location(2) @interpolate(flat) color: vec4<f32>,
     @location(3) spherical_dist: f32,
     @location(4) cylindrical_dist: f32,
+    @location(5) quad_uv: vec2<f32>,
 };
 
 fn fog_spherical_distance(pos: vec3<f32>) -> f32 { return length(pos); }
@@ -20,6 +22,7 @@ fn fog_cylindrical_distance(pos: vec3<f32>) -> f32 { return max(length(pos.xz),
 struct VoxelImm {
     matrix: mat4x4<f32>,
     chunk: vec3<f32>,
+    alpha: f32,
     sun_position: vec3<f32>
 }
 
@@ -307,6 +310,12 @@ fn vs_main(
     out.light_intensity = light_intensity;
     out.uv = in.uv;
 
+    // Faces are always emitted as four vertices, so the vertex index tells
+    // which corner of the quad this is.
+    let corner = in.vertex_index % 4u;
+
+    out.quad_uv = vec2(f32(corner & 1u), f32(corner >> 1u));
+
     return out;
 }
 
@@ -323,7 +332,13 @@ struct FogUniform {
     with_fog: u32,
 }
 
+struct WireframeUniform {
+    color: vec4<f32>,
+    line_width: f32,
+}
+
 @group(1) @binding(0) var<uniform> fog: FogUniform;
+@group(1) @binding(1) var<uniform> wireframe: WireframeUniform;
 
 fn linear_value(dist: f32, start: f32, end: f32) -> f32 {
     if dist <= start { return 0.0; }
@@ -369,6 +384,9 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         );
     }
 
+    // Chunks fade in after loading.
+    f_color.a *= voxel_imm.alpha;
+
     return f_color;
 
     // float brightness = dot(f_color.rgb, vec3(0.2126, 0.7152, 0.0722));
@@ -379,3 +397,16 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // f_bright_color = vec4(0.0, 0.0, 0.0, 1.0);
     //   }
 }
+
+@fragment
+fn fs_wireframe(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Distance to the closest quad edge in pixels.
+    let edge = min(in.quad_uv, vec2(1.0) - in.quad_uv) / max(fwidth(in.quad_uv), vec2(0.0001));
+    let coverage = 1.0 - smoothstep(wireframe.line_width - 0.5, wireframe.line_width + 0.5, min(edge.x, edge.y));
+
+    if coverage <= 0.0 {
+        discard;
+    }
+
+    return vec4(wireframe.color.rgb, wireframe.color.a * coverage);
+}